@@ -0,0 +1,128 @@
+//! Criterion benchmarks for every registered day. Besides timing each solution, the
+//! `rayon`, `masks`, `matchers` and `winners` groups race the current implementations against the
+//! sequential and naive versions they replaced, which the day modules keep around for that.
+
+use aoc2023::input::Loader;
+use aoc2023::{day01, day02, day03, day04, Solution};
+use criterion::{criterion_group, criterion_main, Criterion};
+use std::hint::black_box;
+
+fn input(day: u8) -> String {
+    Loader::new(None)
+        .load_day(day)
+        .expect("benchmarks run against the checked-in inputs")
+}
+
+fn bench_solution<S: Solution>(c: &mut Criterion) {
+    let input = input(S::DAY);
+    let parsed = S::parse(&input).expect("checked-in inputs always parse");
+
+    let mut group = c.benchmark_group(format!("day{:0>2}", S::DAY));
+    group.bench_function("parse", |b| b.iter(|| S::parse(black_box(&input))));
+    group.bench_function("part1", |b| b.iter(|| S::part1(black_box(&parsed))));
+    group.bench_function("part2", |b| b.iter(|| S::part2(black_box(&parsed))));
+    group.finish();
+}
+
+fn days(c: &mut Criterion) {
+    bench_solution::<day01::Day01>(c);
+    bench_solution::<day02::Day02>(c);
+    bench_solution::<day03::Day03>(c);
+    bench_solution::<day04::Day04>(c);
+}
+
+fn rayon(c: &mut Criterion) {
+    let day1 = input(1);
+    let mut group = c.benchmark_group("day01/part1");
+    group.bench_function("rayon", |b| b.iter(|| day01::problem_1(black_box(&day1))));
+    group.bench_function("sequential", |b| {
+        b.iter(|| day01::problem_1_sequential(black_box(&day1)))
+    });
+    group.finish();
+
+    let day2 = input(2);
+    let mut group = c.benchmark_group("day02/parse");
+    group.bench_function("rayon", |b| b.iter(|| day02::parse_games(black_box(&day2))));
+    group.bench_function("sequential", |b| {
+        b.iter(|| day02::parse_games_sequential(black_box(&day2)))
+    });
+    group.finish();
+
+    let day4 = input(4);
+    let mut group = c.benchmark_group("day04/parse");
+    group.bench_function("rayon", |b| b.iter(|| day04::parse_cards(black_box(&day4))));
+    group.bench_function("sequential", |b| {
+        b.iter(|| day04::parse_cards_sequential(black_box(&day4)))
+    });
+    group.finish();
+}
+
+fn masks(c: &mut Criterion) {
+    let day3 = day03::Day03::parse(&input(3)).expect("checked-in inputs always parse");
+    let mut group = c.benchmark_group("day03/part1");
+    group.bench_function("naive", |b| {
+        b.iter(|| day03::problem_1_naive(black_box(&day3)))
+    });
+    group.bench_function("mask", |b| b.iter(|| day03::problem_1(black_box(&day3))));
+    group.finish();
+}
+
+fn matchers(c: &mut Criterion) {
+    let day1 = input(1);
+    let english = day01::DigitVocabulary::english();
+    let mut group = c.benchmark_group("day01/part2");
+    group.bench_function("naive", |b| {
+        b.iter(|| day01::calibrate_naive(black_box(&day1), &english))
+    });
+    group.bench_function("automaton", |b| {
+        b.iter(|| day01::calibrate(black_box(&day1), &english))
+    });
+    group.finish();
+}
+
+/// `size` cards of 10 chosen and 25 winning numbers below 100, from a fixed-seed LCG.
+fn synthetic_deck(size: usize) -> Vec<day04::Card> {
+    let mut seed: u64 = 2023;
+    let mut next = move || {
+        seed = seed
+            .wrapping_mul(6364136223846793005)
+            .wrapping_add(1442695040888963407);
+        (seed >> 33) % 100
+    };
+    let deck: String = (1..=size)
+        .map(|id| {
+            let chosen: Vec<String> = (0..10).map(|_| next().to_string()).collect();
+            let winning: Vec<String> = (0..25).map(|_| next().to_string()).collect();
+            format!("Card {id}: {} | {}\n", chosen.join(" "), winning.join(" "))
+        })
+        .collect();
+    day04::parse_cards(&deck).expect("synthetic decks always parse")
+}
+
+fn winners(c: &mut Criterion) {
+    let deck = synthetic_deck(100_000);
+    let mut group = c.benchmark_group("day04/matches");
+    group.bench_function("naive", |b| {
+        b.iter(|| {
+            black_box(&deck)
+                .iter()
+                .map(|c| c.matches_naive())
+                .sum::<usize>()
+        })
+    });
+    group.bench_function("merge", |b| {
+        b.iter(|| {
+            black_box(&deck)
+                .iter()
+                .map(|c| c.matches_merge())
+                .sum::<usize>()
+        })
+    });
+    group.bench_function("bitset", |b| {
+        b.iter(|| black_box(&deck).iter().map(|c| c.matches()).sum::<usize>())
+    });
+    group.finish();
+}
+
+criterion_group!(benches, days, rayon, masks, matchers, winners);
+criterion_main!(benches);
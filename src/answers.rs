@@ -0,0 +1,74 @@
+use serde::Deserialize;
+use std::fmt;
+use toml::Value;
+
+/// Known answers for one day, read from `answers/NN.toml`. Either part may be left out.
+#[derive(Debug, Default, Clone, PartialEq, Deserialize)]
+pub struct Answers {
+    part1: Option<Value>,
+    part2: Option<Value>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Status {
+    Pass,
+    Fail { expected: String },
+    Missing,
+}
+
+impl Answers {
+    pub fn expected(&self, part: u8) -> Option<String> {
+        let value = match part {
+            1 => self.part1.as_ref(),
+            2 => self.part2.as_ref(),
+            _ => None,
+        }?;
+        match value {
+            Value::String(s) => Some(s.clone()),
+            v => Some(v.to_string()),
+        }
+    }
+
+    pub fn check(&self, part: u8, actual: &str) -> Status {
+        match self.expected(part) {
+            Some(expected) if expected == actual => Status::Pass,
+            Some(expected) => Status::Fail { expected },
+            None => Status::Missing,
+        }
+    }
+}
+
+impl fmt::Display for Status {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Status::Pass => write!(f, "PASS"),
+            Status::Fail { .. } => write!(f, "FAIL"),
+            Status::Missing => write!(f, "MISSING"),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_check() {
+        let answers: Answers = toml::from_str("part1 = 142\npart2 = \"abc\"").unwrap();
+        assert_eq!(answers.check(1, "142"), Status::Pass);
+        assert_eq!(answers.check(2, "abc"), Status::Pass);
+        assert_eq!(
+            answers.check(1, "143"),
+            Status::Fail {
+                expected: "142".to_string()
+            }
+        );
+    }
+
+    #[test]
+    fn test_missing() {
+        let answers: Answers = toml::from_str("part1 = 142").unwrap();
+        assert_eq!(answers.check(2, "281"), Status::Missing);
+        assert_eq!(Answers::default().check(1, "142"), Status::Missing);
+    }
+}
@@ -0,0 +1,112 @@
+use aoc2023::day01::MissingDigits;
+use aoc2023::day02::Set;
+use aoc2023::AocError;
+use clap::{Args, Parser, Subcommand, ValueEnum};
+use std::path::PathBuf;
+
+#[derive(Debug, Parser)]
+#[command(version, about = "Advent of Code 2023 solutions")]
+pub struct Cli {
+    /// Directory holding `input/NN.txt`, defaults to the crate's `data` directory
+    #[arg(long, global = true, env = "AOC_DATA_DIR")]
+    pub data_dir: Option<PathBuf>,
+
+    /// How to print results
+    #[arg(long, global = true, value_enum, default_value_t = Format::Text)]
+    pub format: Format,
+
+    #[command(subcommand)]
+    pub command: Option<Command>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum Format {
+    /// Answers followed by a timing table
+    Text,
+    /// An array of `{day, part, answer, duration_ns, status}` records
+    Json,
+}
+
+#[derive(Debug, Subcommand)]
+pub enum Command {
+    /// Run one or all registered days
+    Run(RunArgs),
+    /// Check every registered day against the answers in the data directory
+    Verify(VerifyArgs),
+    /// Check which day 2 games could have been played with other bags
+    Bags(BagsArgs),
+    /// Run day 1 with a policy for lines that have no digit, listing the lines it affected
+    Calibrate(CalibrateArgs),
+}
+
+#[derive(Debug, Default, Args)]
+pub struct RunArgs {
+    /// Day to run
+    #[arg(short, long, conflicts_with = "all", value_parser = clap::value_parser!(u8).range(1..=25))]
+    pub day: Option<u8>,
+
+    /// Run every registered day (the default when no day is given)
+    #[arg(short, long)]
+    pub all: bool,
+
+    /// Only run this part
+    #[arg(short, long, value_parser = clap::value_parser!(u8).range(1..=2))]
+    pub part: Option<u8>,
+
+    /// Read the puzzle input from this file instead of the data directory, `-` for stdin
+    #[arg(short, long, requires = "day")]
+    pub input: Option<PathBuf>,
+}
+
+#[derive(Debug, Args)]
+pub struct VerifyArgs {
+    /// Only verify this day
+    #[arg(short, long, value_parser = clap::value_parser!(u8).range(1..=25))]
+    pub day: Option<u8>,
+}
+
+#[derive(Debug, Args)]
+pub struct BagsArgs {
+    /// A bag to check, such as "12 red, 13 green, 14 blue"; may be repeated
+    #[arg(short, long = "bag", value_parser = parse_bag)]
+    pub bags: Vec<Set>,
+
+    /// A TOML file listing more bags as `bags = ["12 red, 13 green, 14 blue", ...]`
+    #[arg(short, long)]
+    pub config: Option<PathBuf>,
+
+    /// Read the games from this file instead of the data directory, `-` for stdin
+    #[arg(short, long)]
+    pub input: Option<PathBuf>,
+}
+
+#[derive(Debug, Args)]
+pub struct CalibrateArgs {
+    /// What to do with a line that has no digit: skip, zero or error
+    #[arg(short, long, default_value = "error")]
+    pub missing_digits: MissingDigits,
+
+    /// Only run this part
+    #[arg(short, long, value_parser = clap::value_parser!(u8).range(1..=2))]
+    pub part: Option<u8>,
+
+    /// Read the calibration document from this file instead of the data directory, `-` for stdin
+    #[arg(short, long)]
+    pub input: Option<PathBuf>,
+}
+
+fn parse_bag(bag: &str) -> Result<Set, AocError> {
+    Set::try_from(bag)
+}
+
+impl RunArgs {
+    pub fn runs_part(&self, part: u8) -> bool {
+        self.part.is_none_or(|p| p == part)
+    }
+}
+
+impl CalibrateArgs {
+    pub fn runs_part(&self, part: u8) -> bool {
+        self.part.is_none_or(|p| p == part)
+    }
+}
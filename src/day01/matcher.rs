@@ -0,0 +1,176 @@
+use super::DigitVocabulary;
+
+/// Aho-Corasick automaton over a set of tokens, with every transition resolved up front
+/// so scanning is one table lookup per byte.
+#[derive(Debug, Clone)]
+struct Automaton {
+    next: Vec<[usize; 256]>,
+    /// Indices of the tokens ending at each state, including those reached through failure links.
+    outputs: Vec<Vec<usize>>,
+}
+
+impl Automaton {
+    fn new<'a>(tokens: impl Iterator<Item = &'a [u8]>) -> Self {
+        let mut next: Vec<[usize; 256]> = vec![[0; 256]];
+        let mut outputs: Vec<Vec<usize>> = vec![Vec::new()];
+        let mut child: Vec<[bool; 256]> = vec![[false; 256]];
+
+        for (t, token) in tokens.enumerate() {
+            let mut state = 0;
+            for &b in token {
+                if !child[state][b as usize] {
+                    next.push([0; 256]);
+                    outputs.push(Vec::new());
+                    child.push([false; 256]);
+                    next[state][b as usize] = next.len() - 1;
+                    child[state][b as usize] = true;
+                }
+                state = next[state][b as usize];
+            }
+            outputs[state].push(t);
+        }
+
+        // breadth first, so a state's failure link is finished before its children need it
+        let mut fail: Vec<usize> = vec![0; next.len()];
+        let mut queue: Vec<usize> = (0..256)
+            .filter(|&b| child[0][b])
+            .map(|b| next[0][b])
+            .collect();
+        let mut head = 0;
+        while head < queue.len() {
+            let state = queue[head];
+            head += 1;
+            let inherited = outputs[fail[state]].clone();
+            outputs[state].extend(inherited);
+            for b in 0..256 {
+                match child[state][b] {
+                    true => {
+                        let target = next[state][b];
+                        fail[target] = next[fail[state]][b];
+                        queue.push(target);
+                    }
+                    false => next[state][b] = next[fail[state]][b],
+                }
+            }
+        }
+
+        Automaton { next, outputs }
+    }
+}
+
+/// Finds the first and last tokens of a `DigitVocabulary` in one pass each way.
+#[derive(Debug, Clone)]
+pub struct DigitMatcher {
+    forward: Automaton,
+    backward: Automaton,
+    lengths: Vec<usize>,
+    values: Vec<u32>,
+    max_len: usize,
+}
+
+impl DigitMatcher {
+    pub fn new(vocabulary: &DigitVocabulary) -> Self {
+        let tokens: Vec<(&str, u32)> = vocabulary.tokens().collect();
+        let reversed: Vec<Vec<u8>> = tokens
+            .iter()
+            .map(|(t, _)| t.bytes().rev().collect())
+            .collect();
+        let lengths: Vec<usize> = tokens.iter().map(|(t, _)| t.len()).collect();
+
+        DigitMatcher {
+            forward: Automaton::new(tokens.iter().map(|(t, _)| t.as_bytes())),
+            backward: Automaton::new(reversed.iter().map(|t| t.as_slice())),
+            max_len: lengths.iter().copied().max().unwrap_or(0),
+            values: tokens.iter().map(|(_, n)| *n).collect(),
+            lengths,
+        }
+    }
+
+    /// The earliest starting token, as `(start, token index)`. Ties go to the earlier token.
+    pub fn first(&self, line: &str) -> Option<(usize, usize)> {
+        let mut best: Option<(usize, usize)> = None;
+        let mut state = 0;
+        for (i, &b) in line.as_bytes().iter().enumerate() {
+            // anything matched from here on starts after the best start so far
+            if best.is_some_and(|(start, _)| i >= start + self.max_len) {
+                break;
+            }
+            state = self.forward.next[state][b as usize];
+            for &t in &self.forward.outputs[state] {
+                let found = (i + 1 - self.lengths[t], t);
+                if best.is_none_or(|b| found < b) {
+                    best = Some(found);
+                }
+            }
+        }
+        best
+    }
+
+    /// The latest starting token, as `(start, token index)`. Ties go to the later token.
+    pub fn last(&self, line: &str) -> Option<(usize, usize)> {
+        let bytes = line.as_bytes();
+        let mut state = 0;
+        for (k, &b) in bytes.iter().rev().enumerate() {
+            state = self.backward.next[state][b as usize];
+            // the first match of the reversed scan is the one starting latest in `line`
+            if let Some(&t) = self.backward.outputs[state].iter().max() {
+                return Some((bytes.len() - 1 - k, t));
+            }
+        }
+        None
+    }
+
+    /// The value of the `token`th entry in the vocabulary.
+    pub fn value(&self, token: usize) -> u32 {
+        self.values[token]
+    }
+
+    /// The values of the first and last tokens in `line`, which may overlap.
+    pub fn first_and_last(&self, line: &str) -> Option<(u32, u32)> {
+        let (_, first) = self.first(line)?;
+        let (_, last) = self.last(line)?;
+        Some((self.values[first], self.values[last]))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_overlaps() {
+        let matcher = DigitMatcher::new(&DigitVocabulary::english());
+        assert_eq!(matcher.first_and_last("eightwo"), Some((8, 2)));
+        assert_eq!(matcher.first_and_last("xtwone3four"), Some((2, 4)));
+        assert_eq!(matcher.first_and_last("oneight"), Some((1, 8)));
+        assert_eq!(matcher.first_and_last("7"), Some((7, 7)));
+        assert_eq!(matcher.first_and_last("abc"), None);
+        assert_eq!(matcher.first_and_last(""), None);
+    }
+
+    #[test]
+    fn test_nested_tokens() {
+        // "bc" ends first, but "abcd" starts first
+        let vocabulary = DigitVocabulary::new().with("abcd", 1).with("bc", 2);
+        let matcher = DigitMatcher::new(&vocabulary);
+        assert_eq!(matcher.first("xabcd"), Some((1, 0)));
+        assert_eq!(matcher.last("xabcd"), Some((2, 1)));
+    }
+
+    #[test]
+    fn test_matches_vocabulary_scan() {
+        let vocabulary = DigitVocabulary::english().with("ten", 10).with("en", 11);
+        let matcher = DigitMatcher::new(&vocabulary);
+        let input = std::fs::read_to_string("./data/input/01.txt").expect("error loading input");
+        for line in input
+            .lines()
+            .chain(["tenine", "seventeen", "eleven", "x1", ""])
+        {
+            assert_eq!(
+                matcher.first_and_last(line),
+                vocabulary.first_and_last(line),
+                "{line}"
+            );
+        }
+    }
+}
@@ -1,80 +1,245 @@
+use crate::error::AocError;
+use crate::solution::Solution;
 use rayon::prelude::*;
-pub fn problem_1(input: String) -> u32 {
+use std::str::FromStr;
+
+mod matcher;
+mod vocabulary;
+
+pub use matcher::DigitMatcher;
+pub use vocabulary::DigitVocabulary;
+
+pub struct Day01;
+
+impl Solution for Day01 {
+    const DAY: u8 = 1;
+
+    type Parsed = String;
+    type Answer1 = u32;
+    type Answer2 = u32;
+
+    fn parse(input: &str) -> Result<Self::Parsed, AocError> {
+        Ok(input.to_string())
+    }
+
+    fn part1(input: &Self::Parsed) -> Result<Self::Answer1, AocError> {
+        problem_1(input)
+    }
+
+    fn part2(input: &Self::Parsed) -> Result<Self::Answer2, AocError> {
+        problem_2(input)
+    }
+}
+
+fn no_digit(i: usize, line: &str) -> AocError {
+    AocError::parse(line, line, "no digit in line").on_line(i)
+}
+
+/// How one line of the calibration document was read.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Calibration {
+    /// 1-based line number.
+    pub line: usize,
+    pub first: u32,
+    /// Byte offset where the first digit's token starts.
+    pub first_index: usize,
+    pub last: u32,
+    /// Byte offset where the last digit's token starts.
+    pub last_index: usize,
+}
+
+impl Calibration {
+    /// The two-digit calibration value, `first` in the tens and `last` in the units.
+    pub fn value(&self) -> u32 {
+        self.first * 10 + self.last
+    }
+}
+
+fn calibration_value(i: usize, s: &str) -> Result<Calibration, AocError> {
+    let digit = |(x, c): (usize, char)| c.to_digit(10).map(|d| (x, d));
+    let (first_index, first) = s
+        .char_indices()
+        .find_map(digit)
+        .ok_or_else(|| no_digit(i, s))?;
+    let (last_index, last) = s
+        .char_indices()
+        .rev()
+        .find_map(digit)
+        .ok_or_else(|| no_digit(i, s))?;
+    Ok(Calibration {
+        line: i + 1,
+        first,
+        first_index,
+        last,
+        last_index,
+    })
+}
+
+fn decode(matcher: &DigitMatcher, i: usize, line: &str) -> Result<Calibration, AocError> {
+    let (first_index, first) = matcher.first(line).ok_or_else(|| no_digit(i, line))?;
+    let (last_index, last) = matcher.last(line).ok_or_else(|| no_digit(i, line))?;
+    Ok(Calibration {
+        line: i + 1,
+        first: matcher.value(first),
+        first_index,
+        last: matcher.value(last),
+        last_index,
+    })
+}
+
+/// Reads each line of `input` with `matcher`, so individual lines can be audited.
+/// Blank lines are left out, as `calibrate_with` skips them too.
+pub fn calibrations<'a>(
+    input: &'a str,
+    matcher: &'a DigitMatcher,
+) -> impl Iterator<Item = Result<Calibration, AocError>> + 'a {
     input
-        .par_lines()
-        .map(|s| {
-            let digits = s.chars().map(|c: char| c.to_digit(10));
-            let first: u32 = digits.clone()
-                .find(|d: &Option<u32>| d.is_some())
-                .expect("There will always be one digit in a scrambled string.")
-                .unwrap();
-            let last: u32 = digits.clone()
-                .rfind(|d: &Option<u32>| d.is_some())
-                .expect("There will always be one digit in a scrambled string.")
-                .unwrap();
-            let number: u32 = format!("{first}{last}").parse().expect("the first and last digit will always be positive numbers, meaning that they can only combine to a positive number");
-            number
-        })
-        .sum()
-}
-
-const DIGITS: [&str; 20] = [
-    "0", "1", "2", "3", "4", "5", "6", "7", "8", "9", "zero", "one", "two", "three", "four",
-    "five", "six", "seven", "eight", "nine",
-];
-
-fn str_to_int(s: String) -> u32 {
-    match s.parse::<u32>() {
-        Ok(n) => n,
-        Err(_) => match &*s {
-            "zero" => 0_u32,
-            "one" => 1_u32,
-            "two" => 2_u32,
-            "three" => 3_u32,
-            "four" => 4_u32,
-            "five" => 5_u32,
-            "six" => 6_u32,
-            "seven" => 7_u32,
-            "eight" => 8_u32,
-            "nine" => 9_u32,
-            _ => {
-                panic!("{s}")
-            }
-        },
+        .lines()
+        .enumerate()
+        .filter(|(_, line)| !line.trim().is_empty())
+        .map(|(i, line)| decode(matcher, i, line))
+}
+
+/// What to do with a line that contains no digit at all.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum MissingDigits {
+    /// Leave the line out of the total and list it in the report.
+    Skip,
+    /// Count the line with a calibration value of 0 and list it in the report.
+    Zero,
+    /// Fail with the line number, as the puzzle input should never need the other two.
+    #[default]
+    Error,
+}
+
+impl FromStr for MissingDigits {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "skip" => Ok(MissingDigits::Skip),
+            "zero" => Ok(MissingDigits::Zero),
+            "error" => Ok(MissingDigits::Error),
+            _ => Err(format!("expected `skip`, `zero` or `error`, found `{s}`")),
+        }
     }
 }
 
-pub fn problem_2(input: String) -> u32 {
+/// The outcome of calibrating a whole document.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct CalibrationReport {
+    pub total: u32,
+    /// 1-based numbers of the blank lines, and of the lines skipped by `MissingDigits::Skip`.
+    pub skipped: Vec<usize>,
+    /// 1-based numbers of the lines counted as 0 by `MissingDigits::Zero`.
+    pub zeroed: Vec<usize>,
+}
+
+enum Outcome {
+    Value(u32),
+    Skipped(usize),
+    Zeroed(usize),
+}
+
+/// Applies `policy` to one line. Blank lines are never calibration entries, so they are
+/// skipped whatever the policy, which keeps hand-edited inputs with trailing blank lines working.
+fn outcome(
+    i: usize,
+    line: &str,
+    policy: MissingDigits,
+    read: impl Fn(usize, &str) -> Result<Calibration, AocError>,
+) -> Result<Outcome, AocError> {
+    if line.trim().is_empty() {
+        return Ok(Outcome::Skipped(i + 1));
+    }
+    match (read(i, line), policy) {
+        (Ok(calibration), _) => Ok(Outcome::Value(calibration.value())),
+        (Err(_), MissingDigits::Skip) => Ok(Outcome::Skipped(i + 1)),
+        (Err(_), MissingDigits::Zero) => Ok(Outcome::Zeroed(i + 1)),
+        (Err(e), MissingDigits::Error) => Err(e),
+    }
+}
+
+fn report(outcomes: Vec<Outcome>) -> CalibrationReport {
+    outcomes
+        .into_iter()
+        .fold(CalibrationReport::default(), |mut report, outcome| {
+            match outcome {
+                Outcome::Value(v) => report.total += v,
+                Outcome::Skipped(line) => report.skipped.push(line),
+                Outcome::Zeroed(line) => report.zeroed.push(line),
+            }
+            report
+        })
+}
+
+/// Calibrates `input` reading numerals only, handling digitless lines according to `policy`.
+pub fn problem_1_with(input: &str, policy: MissingDigits) -> Result<CalibrationReport, AocError> {
+    let lines: Vec<&str> = input.lines().collect();
+    let outcomes = lines
+        .par_iter()
+        .enumerate()
+        .map(|(i, s)| outcome(i, s, policy, calibration_value))
+        .collect::<Result<Vec<Outcome>, AocError>>()?;
+    Ok(report(outcomes))
+}
+
+pub fn problem_1(input: &str) -> Result<u32, AocError> {
+    problem_1_with(input, MissingDigits::default()).map(|r| r.total)
+}
+
+/// Sequential version of `problem_1` for benchmarks.
+pub fn problem_1_sequential(input: &str) -> Result<u32, AocError> {
+    let outcomes = input
+        .lines()
+        .enumerate()
+        .map(|(i, s)| outcome(i, s, MissingDigits::default(), calibration_value))
+        .collect::<Result<Vec<Outcome>, AocError>>()?;
+    Ok(report(outcomes).total)
+}
+
+/// Calibrates `input` reading digits with `vocabulary`, handling digitless lines according to `policy`.
+pub fn calibrate_with(
+    input: &str,
+    vocabulary: &DigitVocabulary,
+    policy: MissingDigits,
+) -> Result<CalibrationReport, AocError> {
+    let matcher = DigitMatcher::new(vocabulary);
+    let lines: Vec<&str> = input.lines().collect();
+    let outcomes = lines
+        .par_iter()
+        .enumerate()
+        .map(|(i, line)| outcome(i, line, policy, |i, line| decode(&matcher, i, line)))
+        .collect::<Result<Vec<Outcome>, AocError>>()?;
+    Ok(report(outcomes))
+}
+
+/// Sums the calibration values of `input`, reading digits with `vocabulary`.
+pub fn calibrate(input: &str, vocabulary: &DigitVocabulary) -> Result<u32, AocError> {
+    calibrate_with(input, vocabulary, MissingDigits::default()).map(|r| r.total)
+}
+
+/// `calibrate` searching for each token separately, for benchmarks.
+pub fn calibrate_naive(input: &str, vocabulary: &DigitVocabulary) -> Result<u32, AocError> {
     let scrambled_coordinates: Vec<&str> = input.lines().collect();
 
     let mut sum: u32 = 0;
-    for scrambled_coord in scrambled_coordinates {
-        let mut first: &str = "";
-        let mut last: &str = "";
-        let mut first_index: usize = scrambled_coord.len();
-        let mut last_index: usize = 0;
-
-        for n in DIGITS {
-            if let (Some(i), Some(j)) = (scrambled_coord.find(n), scrambled_coord.rfind(n)) {
-                if i < first_index {
-                    first = n;
-                    first_index = i;
-                }
-                if j >= last_index {
-                    last = n;
-                    last_index = j;
-                }
-            }
+    for (i, scrambled_coord) in scrambled_coordinates.into_iter().enumerate() {
+        if scrambled_coord.trim().is_empty() {
+            continue;
         }
+        let (first, last) = vocabulary
+            .first_and_last(scrambled_coord)
+            .ok_or_else(|| no_digit(i, scrambled_coord))?;
 
-        let first: u32 = str_to_int(first.to_string());
-        let last: u32 = str_to_int(last.to_string());
-
-        let final_number: u32 = format!("{first}{last}").parse().unwrap();
-        sum += final_number;
+        sum += first * 10 + last;
     }
 
-    sum
+    Ok(sum)
+}
+
+pub fn problem_2(input: &str) -> Result<u32, AocError> {
+    calibrate(input, &DigitVocabulary::english())
 }
 
 #[cfg(test)]
@@ -86,22 +251,117 @@ mod tests {
     fn example_1() {
         let input =
             fs::read_to_string("./data/examples/01/problem1Test.txt").expect("error loading input");
-        let result = problem_1(input);
-        assert_eq!(result, 142 as u32);
+        let result = problem_1(&input).unwrap();
+        assert_eq!(result, 142);
     }
 
     #[test]
     fn example_2() {
         let input = fs::read_to_string("./data/examples/01/problem1Test2.txt")
             .expect("error loading input");
-        let result = problem_2(input);
-        assert_eq!(result, 281 as u32);
+        let result = problem_2(&input).unwrap();
+        assert_eq!(result, 281);
     }
     #[test]
     fn edgecases() {
         let edges =
             fs::read_to_string("./data/examples/01/edgecases.txt").expect("error loading input");
-        let result = problem_2(edges);
-        assert_eq!(result, 143 as u32);
+        let result = problem_2(&edges).unwrap();
+        assert_eq!(result, 143);
+        let result = calibrate_naive(&edges, &DigitVocabulary::english()).unwrap();
+        assert_eq!(result, 143);
+    }
+
+    #[test]
+    fn sequential() {
+        let input =
+            fs::read_to_string("./data/examples/01/problem1Test.txt").expect("error loading input");
+        assert_eq!(problem_1_sequential(&input).unwrap(), 142);
+    }
+
+    #[test]
+    fn test_calibrate_vocabulary() {
+        let french = DigitVocabulary::numerals().with_words(&[
+            "zero", "un", "deux", "trois", "quatre", "cinq", "six", "sept", "huit", "neuf",
+        ]);
+        assert_eq!(calibrate("deux1huit\nxtrois", &french).unwrap(), 28 + 33);
+        assert!(calibrate("one", &DigitVocabulary::numerals()).is_err());
+    }
+
+    #[test]
+    fn test_calibrations() {
+        let matcher = DigitMatcher::new(&DigitVocabulary::english());
+        let lines: Vec<Calibration> =
+            calibrations("two1nine\nxtwone3four\n7pqrstsixteen", &matcher)
+                .collect::<Result<_, _>>()
+                .unwrap();
+        assert_eq!(
+            lines[1],
+            Calibration {
+                line: 2,
+                first: 2,
+                first_index: 1,
+                last: 4,
+                last_index: 7,
+            }
+        );
+        let values: Vec<u32> = lines.iter().map(|c| c.value()).collect();
+        assert_eq!(values, vec![29, 24, 76]);
+
+        let err = calibrations("1\nx", &matcher).nth(1).unwrap().unwrap_err();
+        assert_eq!(err.to_string(), "line 2, column 1: no digit in line: \"x\"");
+
+        let lines: Vec<usize> = calibrations("1\n\n  \nthree\n", &matcher)
+            .map(|c| c.unwrap().line)
+            .collect();
+        assert_eq!(lines, vec![1, 4]);
+    }
+
+    #[test]
+    fn test_no_digit() {
+        let input = "1abc2\npqrstuvwx\n";
+        let err = problem_1(input).unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "line 2, column 1: no digit in line: \"pqrstuvwx\""
+        );
+        assert!(problem_2(input).is_err());
+    }
+
+    #[test]
+    fn test_missing_digits() {
+        let input = "1abc2\npqrstuvwx\nthree\n\n";
+        let english = DigitVocabulary::english();
+
+        let skip = problem_1_with(input, MissingDigits::Skip).unwrap();
+        assert_eq!(skip.total, 12);
+        assert_eq!(skip.skipped, vec![2, 3, 4]);
+        assert!(skip.zeroed.is_empty());
+
+        let zero = calibrate_with(input, &english, MissingDigits::Zero).unwrap();
+        assert_eq!(zero.total, 12 + 33);
+        assert_eq!(zero.skipped, vec![4]);
+        assert_eq!(zero.zeroed, vec![2]);
+
+        assert_eq!("zero".parse(), Ok(MissingDigits::Zero));
+        assert!("ignore".parse::<MissingDigits>().is_err());
+
+        let err = calibrate_with(input, &english, MissingDigits::Error).unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "line 2, column 1: no digit in line: \"pqrstuvwx\""
+        );
+    }
+
+    #[test]
+    fn test_blank_lines() {
+        let input = "1abc2\n\n  \ntreb7uchet\n\n";
+        assert_eq!(problem_1(input).unwrap(), 12 + 77);
+        assert_eq!(problem_1_sequential(input).unwrap(), 12 + 77);
+        assert_eq!(problem_2(input).unwrap(), 12 + 77);
+        assert_eq!(
+            calibrate_naive(input, &DigitVocabulary::english()).unwrap(),
+            12 + 77
+        );
     }
 }
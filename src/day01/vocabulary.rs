@@ -0,0 +1,116 @@
+const ENGLISH: [&str; 10] = [
+    "zero", "one", "two", "three", "four", "five", "six", "seven", "eight", "nine",
+];
+
+/// The tokens the calibration decoder recognises, each with the value it stands for.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct DigitVocabulary {
+    tokens: Vec<(String, u32)>,
+}
+
+impl DigitVocabulary {
+    /// A vocabulary that recognises nothing, to build custom ones from.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// The numerals `0` to `9`.
+    pub fn numerals() -> Self {
+        (0..=9).fold(Self::new(), |v, n| v.with(n.to_string(), n))
+    }
+
+    /// Numerals plus the English words `zero` to `nine`, as the puzzle describes.
+    pub fn english() -> Self {
+        Self::numerals().with_words(&ENGLISH)
+    }
+
+    /// Adds a single token.
+    ///
+    /// # Panics
+    ///
+    /// If `token` is empty, as it would match everywhere in every line.
+    pub fn with(mut self, token: impl Into<String>, value: u32) -> Self {
+        let token = token.into();
+        assert!(!token.is_empty(), "digit tokens cannot be empty");
+        self.tokens.push((token, value));
+        self
+    }
+
+    /// Adds a word list where each word stands for its index, so `words[3]` means 3.
+    /// Panics on an empty word, like `with`.
+    pub fn with_words(self, words: &[&str]) -> Self {
+        words
+            .iter()
+            .zip(0..)
+            .fold(self, |v, (word, n)| v.with(*word, n))
+    }
+
+    pub fn tokens(&self) -> impl Iterator<Item = (&str, u32)> {
+        self.tokens.iter().map(|(t, n)| (t.as_str(), *n))
+    }
+
+    /// The values of the first and last tokens in `line`, which may overlap.
+    pub fn first_and_last(&self, line: &str) -> Option<(u32, u32)> {
+        let mut first: Option<(usize, u32)> = None;
+        let mut last: Option<(usize, u32)> = None;
+
+        for (token, n) in self.tokens() {
+            if let (Some(i), Some(j)) = (line.find(token), line.rfind(token)) {
+                if first.is_none_or(|(first_index, _)| i < first_index) {
+                    first = Some((i, n));
+                }
+                if last.is_none_or(|(last_index, _)| j >= last_index) {
+                    last = Some((j, n));
+                }
+            }
+        }
+
+        Some((first?.1, last?.1))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_english() {
+        let english = DigitVocabulary::english();
+        assert_eq!(english.first_and_last("eightwothree"), Some((8, 3)));
+        assert_eq!(english.first_and_last("zoneight234"), Some((1, 4)));
+        assert_eq!(english.first_and_last("xyz"), None);
+        assert_eq!(
+            DigitVocabulary::numerals().first_and_last("two1nine"),
+            Some((1, 1))
+        );
+    }
+
+    #[test]
+    fn test_custom() {
+        let german = DigitVocabulary::numerals().with_words(&[
+            "null", "eins", "zwei", "drei", "vier", "fuenf", "sechs", "sieben", "acht", "neun",
+        ]);
+        assert_eq!(german.first_and_last("xzweiacht7neunx"), Some((2, 9)));
+        assert_eq!(german.first_and_last("eightwo"), None);
+
+        let extended = DigitVocabulary::english()
+            .with("ten", 10)
+            .with("eleven", 11);
+        assert_eq!(extended.first_and_last("tenxeleven"), Some((10, 11)));
+
+        let custom = DigitVocabulary::new().with("*", 5);
+        assert_eq!(custom.first_and_last("a*b1"), Some((5, 5)));
+    }
+
+    #[test]
+    #[should_panic(expected = "digit tokens cannot be empty")]
+    fn test_empty_token() {
+        DigitVocabulary::new().with("", 5).with("1", 1);
+    }
+
+    #[test]
+    #[should_panic(expected = "digit tokens cannot be empty")]
+    fn test_empty_word() {
+        DigitVocabulary::numerals().with_words(&["zero", ""]);
+    }
+}
@@ -0,0 +1,217 @@
+use super::{Game, Set};
+
+/// How the cubes of one set are taken out of the bag. Either way, the set goes back in
+/// before the next one is drawn, as the puzzle describes.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Sampling {
+    /// Each cube is put back before the next is drawn, so a set is a multinomial draw.
+    WithReplacement,
+    /// The cubes of a set are held in hand until it is shown, so a set is a
+    /// multivariate hypergeometric draw.
+    WithoutReplacement,
+}
+
+/// A bag together with the probability that it produced a game.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Estimate {
+    pub bag: Set,
+    pub probability: f64,
+}
+
+fn ln_factorial(n: i32) -> f64 {
+    (2..=n).map(|i| (i as f64).ln()).sum()
+}
+
+fn ln_choose(n: i32, k: i32) -> f64 {
+    ln_factorial(n) - ln_factorial(k) - ln_factorial(n - k)
+}
+
+/// The log probability of drawing `set` from `bag`, negative infinity when it cannot happen.
+fn ln_set_likelihood(set: &Set, bag: &Set, sampling: Sampling) -> f64 {
+    let total: i32 = bag.colours().map(|(_, n)| n).sum();
+    let drawn: i32 = set.colours().map(|(_, n)| n).sum();
+    if drawn == 0 {
+        return 0.0;
+    }
+    match sampling {
+        Sampling::WithReplacement => {
+            set.colours()
+                .fold(ln_factorial(drawn), |ln, (c, k)| match bag.get(c) {
+                    0 => f64::NEG_INFINITY,
+                    n => ln - ln_factorial(k) + k as f64 * (n as f64 / total as f64).ln(),
+                })
+        }
+        Sampling::WithoutReplacement if drawn > total || !set.fits_in(bag) => f64::NEG_INFINITY,
+        Sampling::WithoutReplacement => {
+            set.colours().fold(-ln_choose(total, drawn), |ln, (c, k)| {
+                ln + ln_choose(bag.get(c), k)
+            })
+        }
+    }
+}
+
+fn ln_likelihood(game: &Game, bag: &Set, sampling: Sampling) -> f64 {
+    game.sets
+        .iter()
+        .map(|s| ln_set_likelihood(s, bag, sampling))
+        .sum()
+}
+
+/// The probability that `bag` produced exactly the sets shown in `game`, in order.
+pub fn likelihood(game: &Game, bag: &Set, sampling: Sampling) -> f64 {
+    ln_likelihood(game, bag, sampling).exp()
+}
+
+/// The bag of at most `budget` cubes most likely to have produced `game`, or `None` when
+/// no bag that small could have. Only colours seen in the game are considered, since any
+/// other cube can only make the draws less likely. Ties go to the bag with fewer cubes.
+///
+/// Every composition within the budget is tried, which is fine for the handful of colours
+/// and tens of cubes the puzzle deals in.
+pub fn most_likely_bag(game: &Game, budget: i32, sampling: Sampling) -> Option<Estimate> {
+    let min_bag = game.min_bag();
+    let colours: Vec<(&str, i32)> = match sampling {
+        Sampling::WithReplacement => min_bag.colours().map(|(c, _)| (c, 1)).collect(),
+        Sampling::WithoutReplacement => min_bag.colours().collect(),
+    };
+
+    let mut best: Option<(f64, i32, Set)> = None;
+    let mut counts: Vec<i32> = colours.iter().map(|(_, n)| *n).collect();
+    search(&colours, 0, budget, &mut counts, &mut |counts| {
+        let bag = colours
+            .iter()
+            .zip(counts)
+            .fold(Set::new(), |bag, ((c, _), n)| bag.with(*c, *n));
+        let ln = ln_likelihood(game, &bag, sampling);
+        let total: i32 = counts.iter().sum();
+        let better = best.as_ref().is_none_or(|(best_ln, best_total, _)| {
+            // compare with some slack, as equivalent bags round differently
+            ln > best_ln + 1e-9 || (ln > best_ln - 1e-9 && total < *best_total)
+        });
+        if ln.is_finite() && better {
+            best = Some((ln, total, bag));
+        }
+    });
+
+    best.map(|(ln, _, bag)| Estimate {
+        bag,
+        probability: ln.exp(),
+    })
+}
+
+/// Calls `visit` with every assignment of at least `colours[i].1` cubes to each colour
+/// from `i` on, keeping the total within `budget`.
+fn search(
+    colours: &[(&str, i32)],
+    i: usize,
+    budget: i32,
+    counts: &mut Vec<i32>,
+    visit: &mut impl FnMut(&[i32]),
+) {
+    let used: i32 = counts[..i].iter().sum();
+    let reserved: i32 = colours[i..].iter().map(|(_, n)| n).sum();
+    if used + reserved > budget {
+        return;
+    }
+    if i == colours.len() {
+        visit(counts);
+        return;
+    }
+    let spare = budget - used - reserved;
+    for extra in 0..=spare {
+        counts[i] = colours[i].1 + extra;
+        search(colours, i + 1, budget, counts, visit);
+    }
+    counts[i] = colours[i].1;
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn game(line: &str) -> Game {
+        Game::try_from(line).unwrap()
+    }
+
+    fn close(a: f64, b: f64) -> bool {
+        (a - b).abs() < 1e-9
+    }
+
+    #[test]
+    fn test_likelihood() {
+        let one_red = game("Game 1: 1 red");
+        let bag = Set::rgb(12, 13, 14);
+        assert!(close(
+            likelihood(&one_red, &bag, Sampling::WithoutReplacement),
+            12.0 / 39.0
+        ));
+        assert!(close(
+            likelihood(&one_red, &bag, Sampling::WithReplacement),
+            12.0 / 39.0
+        ));
+
+        // two cubes of different colours, from a bag of one each
+        let pair = game("Game 2: 1 red, 1 blue");
+        let bag = Set::rgb(1, 0, 1);
+        assert!(close(
+            likelihood(&pair, &bag, Sampling::WithoutReplacement),
+            1.0
+        ));
+        assert!(close(
+            likelihood(&pair, &bag, Sampling::WithReplacement),
+            0.5
+        ));
+
+        // sets are independent, so probabilities multiply
+        let twice = game("Game 3: 1 red; 1 red");
+        assert!(close(
+            likelihood(&twice, &Set::rgb(1, 0, 1), Sampling::WithReplacement),
+            0.25
+        ));
+
+        let impossible = game("Game 4: 3 red");
+        let bag = Set::rgb(2, 5, 5);
+        assert_eq!(
+            likelihood(&impossible, &bag, Sampling::WithoutReplacement),
+            0.0
+        );
+        assert_eq!(
+            likelihood(&pair, &Set::rgb(3, 0, 0), Sampling::WithReplacement),
+            0.0
+        );
+    }
+
+    #[test]
+    fn test_most_likely_bag() {
+        let pair = game("Game 1: 1 red, 1 blue");
+        let estimate = most_likely_bag(&pair, 10, Sampling::WithoutReplacement).unwrap();
+        assert_eq!(estimate.bag, Set::rgb(1, 0, 1));
+        assert!(close(estimate.probability, 1.0));
+
+        let estimate = most_likely_bag(&pair, 10, Sampling::WithReplacement).unwrap();
+        assert_eq!(estimate.bag, Set::rgb(1, 0, 1));
+        assert!(close(estimate.probability, 0.5));
+
+        let skewed = game("Game 2: 2 red, 1 blue; 2 red");
+        let estimate = most_likely_bag(&skewed, 12, Sampling::WithReplacement).unwrap();
+        assert_eq!(estimate.bag, Set::rgb(4, 0, 1));
+
+        assert_eq!(
+            most_likely_bag(&skewed, 2, Sampling::WithoutReplacement),
+            None
+        );
+    }
+
+    #[test]
+    fn test_most_likely_bag_fits_the_game() {
+        let example = game("Game 1: 3 blue, 4 red; 1 red, 2 green, 6 blue; 2 green");
+        let estimate = most_likely_bag(&example, 20, Sampling::WithoutReplacement).unwrap();
+        assert!(example.is_possible(&estimate.bag));
+        assert!(
+            estimate.probability
+                > likelihood(&example, &example.min_bag(), Sampling::WithoutReplacement) - 1e-12
+        );
+        let total: i32 = estimate.bag.colours().map(|(_, n)| n).sum();
+        assert!(total <= 20);
+    }
+}
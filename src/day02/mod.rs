@@ -1,198 +1,473 @@
+use crate::error::AocError;
+use crate::solution::Solution;
 use rayon::prelude::*;
+use serde::Deserialize;
+use std::collections::{BTreeMap, BTreeSet};
+use std::fmt;
 
-#[derive(Debug, PartialEq, Copy, Clone)]
-struct Set(i32, i32, i32);
-impl TryFrom<&str> for Set {
-    type Error = String;
+mod inference;
+pub mod stats;
 
-    fn try_from(value: &str) -> Result<Self, Self::Error> {
-        fn get_number(input: &str, color: &str) -> i32 {
-            input
-                .replace(color, "")
-                .trim()
-                .parse()
-                .expect("everything after the color name will be a valid i32")
+pub use inference::{likelihood, most_likely_bag, Estimate, Sampling};
+
+pub struct Day02;
+
+impl Solution for Day02 {
+    const DAY: u8 = 2;
+
+    type Parsed = Vec<Game>;
+    type Answer1 = i32;
+    type Answer2 = i32;
+
+    fn parse(input: &str) -> Result<Self::Parsed, AocError> {
+        parse_games(input)
+    }
+
+    fn part1(games: &Self::Parsed) -> Result<Self::Answer1, AocError> {
+        Ok(problem_1(games))
+    }
+
+    fn part2(games: &Self::Parsed) -> Result<Self::Answer2, AocError> {
+        Ok(problem_2(games))
+    }
+}
+
+/// Counts of cubes by colour, either drawn in one go or held by a bag.
+/// Colours with no cubes are left out, so sets compare equal however they were built.
+#[derive(Debug, Default, PartialEq, Eq, Hash, Clone)]
+pub struct Set {
+    cubes: BTreeMap<String, i32>,
+}
+
+impl Set {
+    /// A set with no cubes at all.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// The red, green and blue set of the puzzle statement.
+    pub fn rgb(red: i32, green: i32, blue: i32) -> Self {
+        Self::new()
+            .with("red", red)
+            .with("green", green)
+            .with("blue", blue)
+    }
+
+    /// Sets the number of `colour` cubes, replacing any previous count.
+    ///
+    /// # Panics
+    ///
+    /// If `count` is negative.
+    pub fn with(mut self, colour: impl Into<String>, count: i32) -> Self {
+        assert!(count >= 0, "cube counts cannot be negative");
+        match count {
+            0 => self.cubes.remove(&colour.into()),
+            n => self.cubes.insert(colour.into(), n),
+        };
+        self
+    }
+
+    /// The number of `colour` cubes, 0 for a colour the set does not mention.
+    pub fn get(&self, colour: &str) -> i32 {
+        self.cubes.get(colour).copied().unwrap_or(0)
+    }
+
+    /// Each colour with its count, in alphabetical order.
+    pub fn colours(&self) -> impl Iterator<Item = (&str, i32)> {
+        self.cubes.iter().map(|(c, n)| (c.as_str(), *n))
+    }
+
+    /// Whether every cube of this set could have come out of `bag`.
+    pub fn fits_in(&self, bag: &Set) -> bool {
+        self.colours().all(|(c, n)| n <= bag.get(c))
+    }
+
+    /// The colours this set has more cubes of than `bag`, with this set's counts.
+    pub fn excess(&self, bag: &Set) -> Set {
+        self.colours()
+            .filter(|(c, n)| *n > bag.get(c))
+            .fold(Set::new(), |excess, (c, n)| excess.with(c, n))
+    }
+
+    /// The smallest set holding both `self` and `other`, colour by colour.
+    pub fn union(mut self, other: &Set) -> Self {
+        for (c, n) in other.colours() {
+            let count = self.cubes.entry(c.to_string()).or_insert(0);
+            *count = (*count).max(n);
         }
+        self
+    }
 
-        let mut r: i32 = 0;
-        let mut g: i32 = 0;
-        let mut b: i32 = 0;
-        for amount in value.trim().split(',') {
-            if amount.ends_with("red") {
-                r = get_number(amount, "red");
-            } else if amount.ends_with("green") {
-                g = get_number(amount, "green");
-            } else if amount.ends_with("blue") {
-                b = get_number(amount, "blue");
-            } else {
-                return Err(amount.to_string());
+    /// The product of the counts of each of `palette`'s colours, so a colour the set
+    /// does not mention makes it 0.
+    pub fn power(&self, palette: &[&str]) -> i32 {
+        palette.iter().map(|c| self.get(c)).product()
+    }
+}
+
+impl fmt::Display for Set {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for (i, (c, n)) in self.colours().enumerate() {
+            if i > 0 {
+                write!(f, ", ")?;
             }
+            write!(f, "{n} {c}")?;
         }
+        Ok(())
+    }
+}
+
+impl TryFrom<&str> for Set {
+    type Error = AocError;
 
-        Ok(Set(r, g, b))
+    /// Parses counts like `3 blue, 4 red`. A colour given twice counts both draws.
+    fn try_from(value: &str) -> Result<Self, Self::Error> {
+        let mut set = Set::new();
+        for amount in value.split(',').map(str::trim) {
+            let (number, colour) = amount
+                .split_once(' ')
+                .ok_or_else(|| AocError::parse(value, amount, "expected a number and a colour"))?;
+            let colour = colour.trim();
+            if colour.is_empty() || !colour.chars().all(char::is_alphabetic) {
+                return Err(AocError::parse(value, colour, "expected a colour name"));
+            }
+            let n = match number.parse::<i32>() {
+                Ok(n) if n < 0 => {
+                    return Err(AocError::parse(
+                        value,
+                        number,
+                        "cube counts cannot be negative",
+                    ))
+                }
+                Ok(n) => n,
+                Err(_) => {
+                    return Err(AocError::parse(
+                        value,
+                        number,
+                        format!("expected a number of {colour} cubes"),
+                    ))
+                }
+            };
+            let count = set.get(colour).checked_add(n).ok_or_else(|| {
+                AocError::parse(value, amount, format!("too many {colour} cubes"))
+            })?;
+            set = set.with(colour, count);
+        }
+
+        Ok(set)
     }
 }
-const BAG_1: Set = Set(12, 13, 14);
 
-fn get_game_id(input: String) -> i32 {
-    let id: i32 = input
-        .split(':')
-        .next()
-        .expect("all strings have at least one colon, and a game id before it")
-        .strip_prefix("Game ")
-        .expect("all strings have a Game substring before the colon")
-        .parse()
-        .expect("everything after Game, but before the colon will be a i32");
-    id
+/// The bag the puzzle asks about in part 1.
+pub fn bag_1() -> Set {
+    Set::rgb(12, 13, 14)
 }
 
-fn get_sets(input: String) -> Vec<Set> {
-    input
-        .split(':')
-        .nth(1)
-        .expect("all strings have exactly one colon, with sets following it")
-        .split(';')
-        .map(|s| s.try_into().expect("error parsing sets"))
-        .collect()
+/// Bags to check games against, read from a TOML file such as
+/// `bags = ["12 red, 13 green, 14 blue", "20 red, 5 green, 9 blue"]`.
+#[derive(Debug, Default, Clone, PartialEq, Eq, Deserialize)]
+pub struct BagConfig {
+    pub bags: Vec<String>,
 }
 
-fn is_possible(input: String) -> bool {
-    let bag = BAG_1;
-    let sets: Vec<Set> = get_sets(input);
-    let invalid_reds: Vec<i32> = sets
-        .iter()
-        .filter_map(|s: &Set| match s.0 > bag.0 {
-            true => Some(s.0),
-            false => None,
-        })
-        .collect();
-    let invalid_greens: Vec<i32> = sets
-        .iter()
-        .filter_map(|s: &Set| match s.1 > bag.1 {
-            true => Some(s.1),
-            false => None,
-        })
-        .collect();
-    let invalid_blues: Vec<i32> = sets
-        .iter()
-        .filter_map(|s: &Set| match s.2 > bag.2 {
-            true => Some(s.2),
-            false => None,
-        })
-        .collect();
+impl BagConfig {
+    pub fn sets(&self) -> Result<Vec<Set>, AocError> {
+        self.bags
+            .iter()
+            .map(|b| Set::try_from(b.as_str()))
+            .collect()
+    }
+}
+
+/// The colours the puzzle describes.
+pub const RGB: [&str; 3] = ["red", "green", "blue"];
 
-    invalid_reds.is_empty() && invalid_greens.is_empty() && invalid_blues.is_empty()
+/// Red, green, blue and every other colour seen in `games`, in alphabetical order.
+/// A game that never shows one of them needed none of it, which makes its power 0.
+pub fn palette(games: &[Game]) -> Vec<&str> {
+    let seen = games.iter().flat_map(|g| &g.sets).flat_map(|s| s.colours());
+    let palette: BTreeSet<&str> = RGB.into_iter().chain(seen.map(|(c, _)| c)).collect();
+    palette.into_iter().collect()
 }
 
-fn get_min_bag(game: String) -> Set {
-    let sets: Vec<Set> = get_sets(game);
-    let min_r: i32 = sets
-        .iter()
-        .map(|s| s.0)
-        .max()
-        .expect("cannot have a game with no sets");
-    let min_g: i32 = sets
-        .iter()
-        .map(|s| s.1)
-        .max()
-        .expect("cannot have a game with no sets");
-    let min_b: i32 = sets
-        .iter()
-        .map(|s| s.2)
-        .max()
-        .expect("cannot have a game with no sets");
-    Set(min_r, min_g, min_b)
+/// One line of the puzzle: a game id and the sets drawn from the bag during it.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Game {
+    pub id: i32,
+    pub sets: Vec<Set>,
 }
 
-pub fn problem_1(input: String) -> i32 {
-    input
-        .par_lines()
-        .filter(|game| is_possible(game.to_string()))
-        .map(|game| get_game_id(game.to_string()))
-        .sum()
+impl Game {
+    /// The fewest cubes of each colour the bag must have held.
+    pub fn min_bag(&self) -> Set {
+        self.sets.iter().fold(Set::new(), |bag, s| bag.union(s))
+    }
+
+    /// Whether every set of the game could have been drawn from `bag`.
+    pub fn is_possible(&self, bag: &Set) -> bool {
+        self.sets.iter().all(|s| s.fits_in(bag))
+    }
+}
+
+impl TryFrom<&str> for Game {
+    type Error = AocError;
+
+    fn try_from(line: &str) -> Result<Self, Self::Error> {
+        let (game, sets) = line
+            .split_once(':')
+            .ok_or_else(|| AocError::parse(line, line, "expected a `:` before the sets"))?;
+        let id: &str = game
+            .strip_prefix("Game ")
+            .ok_or_else(|| AocError::parse(line, game, "expected a game to start with `Game `"))?;
+        let id: i32 = id
+            .parse()
+            .map_err(|_| AocError::parse(line, id, "expected a numeric game id"))?;
+        let sets = sets
+            .split(';')
+            .map(|s| Set::try_from(s).map_err(|e| e.within(line, s)))
+            .collect::<Result<Vec<Set>, AocError>>()?;
+
+        Ok(Game { id, sets })
+    }
 }
 
-pub fn problem_2(input: String) -> i32 {
+pub fn parse_games(input: &str) -> Result<Vec<Game>, AocError> {
+    let lines: Vec<&str> = input.lines().collect();
+    lines
+        .par_iter()
+        .enumerate()
+        .map(|(i, g)| Game::try_from(*g).map_err(|e| e.on_line(i)))
+        .collect()
+}
+
+/// Sequential version of `parse_games` for benchmarks.
+pub fn parse_games_sequential(input: &str) -> Result<Vec<Game>, AocError> {
     input
-        .par_lines()
-        .map(|game| get_min_bag(game.to_string()))
-        .map(|bag: Set| bag.0 * bag.1 * bag.2)
+        .lines()
+        .enumerate()
+        .map(|(i, g)| Game::try_from(g).map_err(|e| e.on_line(i)))
+        .collect()
+}
+
+/// Sums the ids of the games that could have been played with `bag`.
+pub fn problem_1_with(games: &[Game], bag: &Set) -> i32 {
+    games
+        .iter()
+        .filter(|g| g.is_possible(bag))
+        .map(|g| g.id)
         .sum()
 }
 
+pub fn problem_1(games: &[Game]) -> i32 {
+    problem_1_with(games, &bag_1())
+}
+
+/// A game id and the bags it could have been played with.
+pub type PossibleBags = (i32, Vec<Set>);
+
+/// For every game, the subset of `bags` it could have been played with.
+pub fn possible_bags(games: &[Game], bags: &[Set]) -> Vec<PossibleBags> {
+    games
+        .iter()
+        .map(|g| {
+            let min_bag = g.min_bag();
+            let fits = bags.iter().filter(|b| min_bag.fits_in(b)).cloned();
+            (g.id, fits.collect())
+        })
+        .collect()
+}
+
+pub fn problem_2(games: &[Game]) -> i32 {
+    let palette = palette(games);
+    games.iter().map(|g| g.min_bag().power(&palette)).sum()
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
     use std::fs;
 
+    fn example(name: &str) -> Vec<Game> {
+        let input = fs::read_to_string(format!("./data/examples/02/{name}.txt"))
+            .expect("error loading input");
+        parse_games(&input).unwrap()
+    }
+
     #[test]
     fn test_game_id() {
-        let games: Vec<i32> = fs::read_to_string("./data/examples/02/problem1.txt")
-            .expect("error loading input")
-            .lines()
-            .map(|g: &str| get_game_id(g.to_string()))
-            .collect();
-        assert_eq!(games[0], 1);
-        assert_eq!(games[1], 2);
-        assert_eq!(games[2], 3);
-        assert_eq!(games[3], 4);
-        assert_eq!(games[4], 5);
-        assert_eq!(games[5], 6);
+        let ids: Vec<i32> = example("problem1").iter().map(|g| g.id).collect();
+        assert_eq!(ids, vec![1, 2, 3, 4, 5, 6]);
     }
 
     #[test]
     fn test_sets() {
-        let games: Vec<Vec<Set>> = fs::read_to_string("./data/examples/02/problem1.txt")
-            .expect("error loading input")
-            .lines()
-            .map(|g: &str| get_sets(g.to_string()))
-            .collect();
-        assert_eq!(games[0], vec![Set(4, 0, 3), Set(1, 2, 6), Set(0, 2, 0)]);
-        assert_eq!(games[1], vec![Set(0, 2, 1), Set(1, 3, 4), Set(0, 1, 1)]);
-        assert_eq!(games[2], vec![Set(20, 8, 6), Set(4, 13, 5), Set(1, 5, 0)]);
-        assert_eq!(games[3], vec![Set(3, 1, 6), Set(6, 3, 0), Set(14, 3, 15)]);
-        assert_eq!(games[4], vec![Set(6, 3, 1), Set(1, 2, 2)]);
-        assert_eq!(games[5], vec![Set(12, 0, 0), Set(1, 0, 0)]);
+        let games: Vec<Vec<Set>> = example("problem1").into_iter().map(|g| g.sets).collect();
+        assert_eq!(
+            games[0],
+            vec![Set::rgb(4, 0, 3), Set::rgb(1, 2, 6), Set::rgb(0, 2, 0)]
+        );
+        assert_eq!(
+            games[1],
+            vec![Set::rgb(0, 2, 1), Set::rgb(1, 3, 4), Set::rgb(0, 1, 1)]
+        );
+        assert_eq!(
+            games[2],
+            vec![Set::rgb(20, 8, 6), Set::rgb(4, 13, 5), Set::rgb(1, 5, 0)]
+        );
+        assert_eq!(
+            games[3],
+            vec![Set::rgb(3, 1, 6), Set::rgb(6, 3, 0), Set::rgb(14, 3, 15)]
+        );
+        assert_eq!(games[4], vec![Set::rgb(6, 3, 1), Set::rgb(1, 2, 2)]);
+        assert_eq!(games[5], vec![Set::rgb(12, 0, 0), Set::rgb(1, 0, 0)]);
     }
 
     #[test]
     fn test_possible() {
-        let games: Vec<bool> = fs::read_to_string("./data/examples/02/problem1.txt")
-            .expect("error loading input")
-            .lines()
-            .map(|g: &str| is_possible(g.to_string()))
+        let games: Vec<bool> = example("problem1")
+            .iter()
+            .map(|g| g.is_possible(&bag_1()))
             .collect();
-        assert_eq!(games[0], true);
-        assert_eq!(games[1], true);
-        assert_eq!(games[2], false);
-        assert_eq!(games[3], false);
-        assert_eq!(games[4], true);
-        assert_eq!(games[5], true);
+        assert!(games[0]);
+        assert!(games[1]);
+        assert!(!games[2]);
+        assert!(!games[3]);
+        assert!(games[4]);
+        assert!(games[5]);
     }
     #[test]
     fn test_min_bag() {
-        let bags: Vec<Set> = fs::read_to_string("./data/examples/02/problem1.txt")
-            .expect("error loading input")
-            .lines()
-            .map(|b: &str| get_min_bag(b.to_string()))
-            .collect();
-        assert_eq!(bags[0], Set(4, 2, 6));
-        assert_eq!(bags[1], Set(1, 3, 4));
-        assert_eq!(bags[2], Set(20, 13, 6));
-        assert_eq!(bags[3], Set(14, 3, 15));
-        assert_eq!(bags[4], Set(6, 3, 2));
+        let bags: Vec<Set> = example("problem1").iter().map(|g| g.min_bag()).collect();
+        assert_eq!(bags[0], Set::rgb(4, 2, 6));
+        assert_eq!(bags[1], Set::rgb(1, 3, 4));
+        assert_eq!(bags[2], Set::rgb(20, 13, 6));
+        assert_eq!(bags[3], Set::rgb(14, 3, 15));
+        assert_eq!(bags[4], Set::rgb(6, 3, 2));
     }
 
     #[test]
     fn test_example_1() {
-        let example =
-            fs::read_to_string("./data/examples/02/problem1.txt").expect("error loading input");
-        assert_eq!(problem_1(example), 14);
+        assert_eq!(problem_1(&example("problem1")), 14);
     }
     #[test]
     fn test_example_2() {
-        let example =
-            fs::read_to_string("./data/examples/02/problem2.txt").expect("error loading input");
-        assert_eq!(problem_2(example), 2286);
+        assert_eq!(problem_2(&example("problem2")), 2286);
+    }
+
+    #[test]
+    fn test_parse_sequential() {
+        let input =
+            fs::read_to_string("./data/examples/02/problem1.txt").expect("error loading input");
+        assert_eq!(parse_games_sequential(&input).unwrap(), example("problem1"));
+    }
+
+    #[test]
+    fn test_parse_errors() {
+        let err = Game::try_from("Game 1: 3 blue; 4").unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "line 1, column 17: expected a number and a colour: \"4\""
+        );
+
+        let err = Game::try_from("Game 1: 3 blue; 4 red!").unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "line 1, column 19: expected a colour name: \"red!\""
+        );
+
+        let err = Game::try_from("Game x: 3 blue").unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "line 1, column 6: expected a numeric game id: \"x\""
+        );
+
+        let err = Game::try_from("Round 1: 3 blue").unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "line 1, column 1: expected a game to start with `Game `: \"Round 1\""
+        );
+
+        let err = parse_games("Game 1: 3 blue\nGame 2: many red").unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "line 2, column 9: expected a number of red cubes: \"many\""
+        );
+
+        let err = Game::try_from("Game 1: -3 red").unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "line 1, column 9: cube counts cannot be negative: \"-3\""
+        );
+
+        let err = Game::try_from("Game 1: 2147483647 red, 1 red").unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "line 1, column 25: too many red cubes: \"1 red\""
+        );
+    }
+
+    #[test]
+    #[should_panic(expected = "cube counts cannot be negative")]
+    fn test_negative_count() {
+        Set::new().with("red", -1);
+    }
+
+    #[test]
+    fn test_missing_colour_power() {
+        let games = parse_games("Game 1: 3 red, 4 green\nGame 2: 1 red, 1 green, 1 blue").unwrap();
+        assert_eq!(games[0].min_bag().power(&RGB), 0);
+        assert_eq!(problem_2(&games), 1);
+    }
+
+    #[test]
+    fn test_other_colours() {
+        let games = parse_games("Game 7: 2 yellow, 1 red; 5 yellow; 3 purple, 2 red").unwrap();
+        assert_eq!(
+            games[0].min_bag(),
+            Set::new()
+                .with("yellow", 5)
+                .with("red", 2)
+                .with("purple", 3)
+        );
+        // no green or blue cubes were needed, so the power is 0
+        assert_eq!(problem_2(&games), 0);
+        assert_eq!(problem_1(&games), 0);
+        let rainbow = parse_games("Game 8: 1 red, 2 green, 3 blue; 2 yellow").unwrap();
+        assert_eq!(palette(&rainbow), vec!["blue", "green", "red", "yellow"]);
+        assert_eq!(problem_2(&rainbow), 12);
+
+        let bag = bag_1().with("yellow", 5).with("purple", 3);
+        assert_eq!(problem_1_with(&games, &bag), 7);
+        assert_eq!(
+            Set::try_from("1 red, 2 red").unwrap(),
+            Set::new().with("red", 3)
+        );
+        assert_eq!(Set::try_from("0 blue, 2 red").unwrap(), Set::rgb(2, 0, 0));
+    }
+
+    #[test]
+    fn test_other_bags() {
+        let games = example("problem1");
+        assert_eq!(problem_1_with(&games, &bag_1()), 14);
+        assert_eq!(problem_1_with(&games, &Set::rgb(20, 13, 15)), 21);
+        assert_eq!(problem_1_with(&games, &Set::rgb(0, 0, 0)), 0);
+
+        let small = Set::rgb(4, 3, 6);
+        let bags = possible_bags(&games, &[bag_1(), small.clone()]);
+        assert_eq!(bags[0], (1, vec![bag_1(), small]));
+        assert_eq!(bags[2], (3, vec![]));
+        assert_eq!(bags[5], (6, vec![bag_1()]));
+    }
+
+    #[test]
+    fn test_bag_config() {
+        let config: BagConfig =
+            toml::from_str("bags = [\"12 red, 13 green, 14 blue\", \"1 blue\"]").unwrap();
+        assert_eq!(config.sets().unwrap(), vec![bag_1(), Set::rgb(0, 0, 1)]);
+        assert_eq!(bag_1().to_string(), "14 blue, 13 green, 12 red");
+        assert_eq!(
+            Set::try_from(bag_1().to_string().as_str()).unwrap(),
+            bag_1()
+        );
     }
 }
@@ -0,0 +1,209 @@
+use super::{palette, Game, Set};
+use std::collections::BTreeMap;
+use std::fmt::{self, Write};
+
+/// How many cubes of one colour the games show.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ColourStats {
+    pub colour: String,
+    /// The most cubes of this colour shown in a single set.
+    pub max: i32,
+    /// The mean number of cubes of this colour per set, counting sets without it as 0.
+    pub mean: f64,
+}
+
+/// A game that could not have been played with the bag, and the colours it drew too many of.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Impossible {
+    pub id: i32,
+    /// Each colour with the most cubes of it the game showed at once.
+    pub excess: Set,
+}
+
+/// Summary statistics of a list of games against one bag.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Stats {
+    pub games: usize,
+    pub bag: Set,
+    /// One entry per colour seen in any game, in alphabetical order.
+    pub colours: Vec<ColourStats>,
+    pub impossible: Vec<Impossible>,
+    /// How many games have each number of sets.
+    pub set_counts: BTreeMap<usize, usize>,
+    /// The id and power of the game whose minimum bag has the highest power, the first on ties.
+    pub highest_power: Option<(i32, i32)>,
+}
+
+impl Stats {
+    pub fn new(games: &[Game], bag: &Set) -> Self {
+        let sets: Vec<&Set> = games.iter().flat_map(|g| &g.sets).collect();
+        let mut totals: BTreeMap<&str, (i32, i32)> = BTreeMap::new();
+        for (colour, n) in sets.iter().flat_map(|s| s.colours()) {
+            let (max, sum) = totals.entry(colour).or_default();
+            *max = (*max).max(n);
+            *sum += n;
+        }
+        let colours = totals
+            .into_iter()
+            .map(|(colour, (max, sum))| ColourStats {
+                colour: colour.to_string(),
+                max,
+                mean: sum as f64 / sets.len() as f64,
+            })
+            .collect();
+
+        let impossible = games
+            .iter()
+            .map(|g| Impossible {
+                id: g.id,
+                excess: g.min_bag().excess(bag),
+            })
+            .filter(|i| i.excess != Set::new())
+            .collect();
+
+        let mut set_counts = BTreeMap::new();
+        for game in games {
+            *set_counts.entry(game.sets.len()).or_default() += 1;
+        }
+
+        let palette = palette(games);
+        let highest_power = games
+            .iter()
+            .map(|g| (g.id, g.min_bag().power(&palette)))
+            .reduce(|best, g| match g.1 > best.1 {
+                true => g,
+                false => best,
+            });
+
+        Stats {
+            games: games.len(),
+            bag: bag.clone(),
+            colours,
+            impossible,
+            set_counts,
+            highest_power,
+        }
+    }
+
+    /// The statistics as `section,key,colour,value` rows under a header.
+    pub fn to_csv(&self) -> String {
+        let mut csv = String::from("section,key,colour,value\n");
+        // writing to a `String` cannot fail
+        let mut row =
+            |section: &str, key: &dyn fmt::Display, colour: &str, value: &dyn fmt::Display| {
+                writeln!(csv, "{section},{key},{colour},{value}").expect("writing to a String");
+            };
+        row("games", &"", "", &self.games);
+        for c in &self.colours {
+            row("colour", &"max", &c.colour, &c.max);
+            row("colour", &"mean", &c.colour, &format!("{:.2}", c.mean));
+        }
+        for i in &self.impossible {
+            for (colour, n) in i.excess.colours() {
+                row("impossible", &i.id, colour, &n);
+            }
+        }
+        for (sets, games) in &self.set_counts {
+            row("sets", sets, "", games);
+        }
+        if let Some((id, power)) = self.highest_power {
+            row("highest_power", &id, "", &power);
+        }
+        csv
+    }
+}
+
+/// Renders the statistics as aligned text tables.
+impl fmt::Display for Stats {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "{} games", self.games)?;
+
+        writeln!(f)?;
+        writeln!(f, "{:<8} {:>6} {:>8}", "Colour", "Max", "Mean")?;
+        for c in &self.colours {
+            writeln!(f, "{:<8} {:>6} {:>8.2}", c.colour, c.max, c.mean)?;
+        }
+
+        writeln!(f)?;
+        writeln!(f, "Impossible with {}:", self.bag)?;
+        writeln!(
+            f,
+            "{:>6} {:<8} {:>6} {:>6}",
+            "Game", "Colour", "Drawn", "Bag"
+        )?;
+        for i in &self.impossible {
+            for (colour, n) in i.excess.colours() {
+                let bag = self.bag.get(colour);
+                writeln!(f, "{:>6} {:<8} {:>6} {:>6}", i.id, colour, n, bag)?;
+            }
+        }
+
+        writeln!(f)?;
+        writeln!(f, "{:>6} {:>6}", "Sets", "Games")?;
+        for (sets, games) in &self.set_counts {
+            writeln!(f, "{sets:>6} {games:>6}")?;
+        }
+
+        if let Some((id, power)) = self.highest_power {
+            writeln!(f)?;
+            writeln!(f, "Highest power: game {id} ({power})")?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::day02::{bag_1, parse_games};
+    use std::fs;
+
+    fn example() -> Stats {
+        let input =
+            fs::read_to_string("./data/examples/02/problem1.txt").expect("error loading input");
+        Stats::new(&parse_games(&input).unwrap(), &bag_1())
+    }
+
+    #[test]
+    fn test_stats() {
+        let stats = example();
+        assert_eq!(stats.games, 6);
+        assert_eq!(
+            stats.colours[2],
+            ColourStats {
+                colour: "red".to_string(),
+                max: 20,
+                mean: 74.0 / 16.0,
+            }
+        );
+        assert_eq!(
+            stats.impossible,
+            vec![
+                Impossible {
+                    id: 3,
+                    excess: Set::rgb(20, 0, 0),
+                },
+                Impossible {
+                    id: 4,
+                    excess: Set::rgb(14, 0, 15),
+                },
+            ]
+        );
+        assert_eq!(stats.set_counts, BTreeMap::from([(2, 2), (3, 4)]));
+        assert_eq!(stats.highest_power, Some((3, 1560)));
+    }
+
+    #[test]
+    fn test_render() {
+        let stats = example();
+        let csv = stats.to_csv();
+        assert!(csv.starts_with("section,key,colour,value\ngames,,,6\n"));
+        assert!(csv.contains("colour,max,red,20\n"));
+        assert!(csv.contains("impossible,4,blue,15\nimpossible,4,red,14\n"));
+        assert!(csv.ends_with("sets,3,,4\nhighest_power,3,,1560\n"));
+
+        let table = stats.to_string();
+        assert!(table.contains("     4 blue         15     14\n"));
+        assert!(table.contains("Highest power: game 3 (1560)"));
+    }
+}
@@ -1,69 +1,97 @@
-#[derive(Debug, Eq, PartialEq, Copy, Clone, PartialOrd, Ord)]
-struct Point(i32, i32);
-
-fn find_symbols(rows: Vec<&str>) -> Vec<Point> {
-    let mut symbols: Vec<Point> = Vec::new();
-    for (y, row) in rows.iter().enumerate() {
-        for (x, c) in row.char_indices() {
-            if !c.is_alphanumeric() && c != '.' {
-                symbols.push(Point(x.try_into().unwrap(), y.try_into().unwrap()))
-            }
-        }
+use crate::error::AocError;
+use crate::grid::Grid;
+pub use crate::grid::Point;
+use crate::solution::Solution;
+use std::iter;
+use std::ops::Range;
+
+pub struct Day03;
+
+impl Solution for Day03 {
+    const DAY: u8 = 3;
+
+    type Parsed = Grid<char>;
+    type Answer1 = i32;
+    type Answer2 = i32;
+
+    fn parse(input: &str) -> Result<Self::Parsed, AocError> {
+        Grid::try_from(input)
     }
-    symbols
-}
 
-fn find_targets(schematic: &str) -> Vec<Point> {
-    let rows: Vec<&str> = schematic.lines().collect();
-    let max_x = rows[0].len() as i32 - 1;
-    let max_y = rows.len() as i32 - 1;
+    fn part1(schematic: &Self::Parsed) -> Result<Self::Answer1, AocError> {
+        problem_1(schematic)
+    }
 
-    let symbols = find_symbols(rows);
+    fn part2(schematic: &Self::Parsed) -> Result<Self::Answer2, AocError> {
+        problem_2(schematic)
+    }
+}
 
-    let mut points = Vec::new();
-    for p in symbols {
-        let x = p.0;
-        let y = p.1;
+fn is_symbol(c: &char) -> bool {
+    !c.is_alphanumeric() && *c != '.'
+}
 
-        #[rustfmt::skip]
-        let possibilities = vec![
-            Point(x-1, y+1), Point(x, y+1), Point(x + 1, y+1),
-            Point(x-1, y), Point(x, y), Point(x + 1, y),
-            Point(x-1, y-1), Point(x, y-1), Point(x + 1, y-1),
-        ];
+fn find_symbols(schematic: &Grid<char>) -> Vec<Point> {
+    schematic.find_all(is_symbol)
+}
 
-        for point in possibilities {
-            if (0..=max_x).contains(&point.0)
-                && (0..=max_y).contains(&point.1)
-                && !points.contains(&point)
-            {
-                points.push(point)
+/// Marks every cell that is a symbol or touches one, so adjacency is a single lookup.
+fn target_mask(schematic: &Grid<char>) -> Grid<bool> {
+    let mut mask = Grid::filled(schematic.width(), schematic.height(), false);
+    for p in find_symbols(schematic) {
+        for point in iter::once(p).chain(schematic.neighbours_8(p)) {
+            *mask.get_mut(point).expect("neighbours are in bounds") = true;
+        }
+    }
+    mask
+}
+
+/// Every run of ASCII digits in `row`, as the span of columns it covers.
+fn tokenize(row: &[char]) -> Vec<Range<usize>> {
+    let mut tokens: Vec<Range<usize>> = Vec::new();
+    let mut start: Option<usize> = None;
+    for (x, c) in row.iter().enumerate() {
+        match (c.is_ascii_digit(), start) {
+            (true, None) => start = Some(x),
+            (false, Some(s)) => {
+                tokens.push(s..x);
+                start = None;
             }
+            _ => {}
         }
     }
-    points.sort();
-    points
+    if let Some(s) = start {
+        tokens.push(s..row.len());
+    }
+    tokens
 }
 
-fn get_num_points(schematic: &str) -> Vec<Vec<(i32, Vec<Point>)>> {
-    let mut number_loc: Vec<Vec<(i32, Vec<Point>)>> = Vec::new();
-    for (y, line) in schematic.lines().enumerate() {
-        let numbers: Vec<&str> = line
-            .split(|c: char| !c.is_ascii_digit())
-            .filter(|i| !i.is_empty())
-            .collect();
+/// A number and the cells its digits cover.
+type Located = (i32, Vec<Point>);
 
-        let mut line_number_loc: Vec<(i32, Vec<Point>)> = Vec::new();
-        for n in numbers.iter() {
-            let mut points: Vec<Point> = Vec::new();
+/// The value of a run of digits, or a parse error pointing at it when it does not fit in an `i32`.
+fn parse_number(digits: &[char], x: usize, y: usize) -> Result<i32, AocError> {
+    digits
+        .iter()
+        .try_fold(0i32, |n, c| {
+            n.checked_mul(10)?.checked_add(c.to_digit(10)? as i32)
+        })
+        .ok_or_else(|| AocError::Parse {
+            line: y + 1,
+            column: x + 1,
+            text: digits.iter().collect(),
+            reason: "number too large".to_string(),
+        })
+}
 
-            let start = line.find(n).expect("we know n is in line");
-            let end = start + n.len() - 1;
-            let n: i32 = n.parse().expect("all numbers will be in the i32 range");
+fn get_num_points(schematic: &Grid<char>) -> Result<Vec<Vec<Located>>, AocError> {
+    let mut number_loc: Vec<Vec<Located>> = Vec::new();
+    for (y, row) in schematic.rows().enumerate() {
+        let mut line_number_loc: Vec<Located> = Vec::new();
+        for span in tokenize(row) {
+            let n = parse_number(&row[span.clone()], span.start, y)?;
 
-            for x in start..=end {
-                points.push(Point(x as i32, y as i32))
-            }
+            let points: Vec<Point> = span.map(|x| Point(x as i32, y as i32)).collect();
 
             line_number_loc.push((n, points));
         }
@@ -71,43 +99,172 @@ fn get_num_points(schematic: &str) -> Vec<Vec<(i32, Vec<Point>)>> {
         number_loc.push(line_number_loc);
     }
 
-    number_loc
+    Ok(number_loc)
 }
 
-pub fn problem_1(schematic: &str) -> i32 {
-    let numbers: Vec<Vec<(i32, Vec<Point>)>> = get_num_points(schematic);
-    let targets = find_targets(schematic);
+pub fn problem_1(schematic: &Grid<char>) -> Result<i32, AocError> {
+    let numbers: Vec<Vec<Located>> = get_num_points(schematic)?;
+    let targets = target_mask(schematic);
 
     let mut final_numbers: Vec<i32> = Vec::new();
     for entry in numbers.into_iter() {
         for (n, points) in entry.into_iter() {
-            for point in points {
-                if targets.contains(&point) {
-                    final_numbers.push(n)
-                }
+            if points
+                .iter()
+                .any(|point| targets.get(*point) == Some(&true))
+            {
+                final_numbers.push(n)
+            }
+        }
+    }
+
+    Ok(final_numbers.iter().sum())
+}
+
+/// `problem_1` checking adjacency against a list of target points, for benchmarks.
+pub fn problem_1_naive(schematic: &Grid<char>) -> Result<i32, AocError> {
+    let mut targets: Vec<Point> = Vec::new();
+    for p in find_symbols(schematic) {
+        for point in iter::once(p).chain(schematic.neighbours_8(p)) {
+            if !targets.contains(&point) {
+                targets.push(point)
+            }
+        }
+    }
+
+    Ok(get_num_points(schematic)?
+        .into_iter()
+        .flatten()
+        .filter(|(_, points)| points.iter().any(|point| targets.contains(point)))
+        .map(|(n, _)| n)
+        .sum())
+}
+
+/// Labels every digit cell with the index of its number in `numbers`.
+fn number_mask(schematic: &Grid<char>, numbers: &[Located]) -> Grid<Option<usize>> {
+    let mut mask = Grid::filled(schematic.width(), schematic.height(), None);
+    for (i, (_, points)) in numbers.iter().enumerate() {
+        for point in points {
+            *mask.get_mut(*point).expect("numbers are in bounds") = Some(i);
+        }
+    }
+    mask
+}
+
+/// A symbol in the schematic and the numbers touching it, as indices into `Schematic::numbers`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Symbol {
+    pub symbol: char,
+    pub position: Point,
+    pub parts: Vec<usize>,
+}
+
+/// A number in the schematic and the symbols touching it, as indices into `Schematic::symbols`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Number {
+    pub value: i32,
+    pub points: Vec<Point>,
+    pub symbols: Vec<usize>,
+}
+
+/// Every number and symbol in a schematic, linked to each other by adjacency.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Schematic {
+    pub numbers: Vec<Number>,
+    pub symbols: Vec<Symbol>,
+}
+
+impl Schematic {
+    pub fn analyse(schematic: &Grid<char>) -> Result<Self, AocError> {
+        let found: Vec<Located> = get_num_points(schematic)?.into_iter().flatten().collect();
+        let labels = number_mask(schematic, &found);
+
+        let mut numbers: Vec<Number> = found
+            .into_iter()
+            .map(|(value, points)| Number {
+                value,
+                points,
+                symbols: Vec::new(),
+            })
+            .collect();
+
+        let mut symbols: Vec<Symbol> = Vec::new();
+        for position in find_symbols(schematic) {
+            let mut parts: Vec<usize> = schematic
+                .neighbours_8(position)
+                .filter_map(|p| *labels.get(p).expect("neighbours are in bounds"))
+                .collect();
+            parts.sort();
+            parts.dedup();
+
+            for &n in &parts {
+                numbers[n].symbols.push(symbols.len());
             }
+            symbols.push(Symbol {
+                symbol: *schematic.get(position).expect("symbols are in bounds"),
+                position,
+                parts,
+            });
         }
+
+        Ok(Schematic { numbers, symbols })
+    }
+
+    /// Numbers touching at least one symbol.
+    pub fn part_numbers(&self) -> impl Iterator<Item = &Number> {
+        self.numbers.iter().filter(|n| !n.symbols.is_empty())
+    }
+
+    /// Numbers touching at least one `symbol`.
+    pub fn adjacent_to(&self, symbol: char) -> impl Iterator<Item = &Number> {
+        self.numbers
+            .iter()
+            .filter(move |n| n.symbols.iter().any(|&s| self.symbols[s].symbol == symbol))
     }
 
-    final_numbers.iter().sum()
+    pub fn parts_of<'a>(&'a self, symbol: &'a Symbol) -> impl Iterator<Item = &'a Number> {
+        symbol.parts.iter().map(|&n| &self.numbers[n])
+    }
+
+    pub fn symbols_of<'a>(&'a self, number: &'a Number) -> impl Iterator<Item = &'a Symbol> {
+        number.symbols.iter().map(|&s| &self.symbols[s])
+    }
 }
 
-// pub fn problem_2(input: &str) -> i32 {
-//     todo!()
-// }
+pub fn problem_2(schematic: &Grid<char>) -> Result<i32, AocError> {
+    let schematic = Schematic::analyse(schematic)?;
+    Ok(schematic
+        .symbols
+        .iter()
+        .filter(|s| s.symbol == '*')
+        .filter_map(|gear| match gear.parts[..] {
+            [a, b] => Some(schematic.numbers[a].value * schematic.numbers[b].value),
+            _ => None,
+        })
+        .sum())
+}
 
 #[cfg(test)]
 mod tests {
     use super::*;
     use std::fs;
 
+    fn grid(schematic: &str) -> Grid<char> {
+        schematic.try_into().unwrap()
+    }
+
+    fn find_targets(schematic: &Grid<char>) -> Vec<Point> {
+        let mut points = target_mask(schematic).find_all(|t| *t);
+        points.sort();
+        points
+    }
+
     #[test]
     fn test_get_symbols() {
         let example =
             fs::read_to_string("./data/examples/03/problem1.txt").expect("error loading input");
-        let example: Vec<&str> = example.lines().collect();
         assert_eq!(
-            find_symbols(example),
+            find_symbols(&grid(&example)),
             vec![
                 Point(3, 1),
                 Point(6, 3),
@@ -169,8 +326,8 @@ mod tests {
         ];
         expected2.sort();
 
-        assert_eq!(find_targets(&example1), expected1);
-        assert_eq!(find_targets(&example2), expected2);
+        assert_eq!(find_targets(&grid(&example1)), expected1);
+        assert_eq!(find_targets(&grid(&example2)), expected2);
     }
 
     #[test]
@@ -203,14 +360,109 @@ mod tests {
             (633, vec![Point(6, 0), Point(7, 0), Point(8, 0)]),
         ];
 
-        assert_eq!(get_num_points(&example1), vec![expected1]);
-        assert_eq!(get_num_points(&example2), vec![expected2]);
+        assert_eq!(get_num_points(&grid(&example1)).unwrap(), vec![expected1]);
+        assert_eq!(get_num_points(&grid(&example2)).unwrap(), vec![expected2]);
+    }
+
+    #[test]
+    fn test_tokenize() {
+        let tokens = |row: &str| tokenize(&row.chars().collect::<Vec<char>>());
+        assert_eq!(tokens("..35..35."), vec![2..4, 6..8]);
+        assert_eq!(tokens("467..67"), vec![0..3, 5..7]);
+        assert_eq!(tokens("1.2"), vec![0..1, 2..3]);
+        assert_eq!(tokens("..."), vec![]);
+    }
+
+    #[test]
+    fn test_repeated_num_points() {
+        assert_eq!(
+            get_num_points(&grid("..35..35.")).unwrap(),
+            vec![vec![
+                (35, vec![Point(2, 0), Point(3, 0)]),
+                (35, vec![Point(6, 0), Point(7, 0)]),
+            ]]
+        );
+        assert_eq!(
+            get_num_points(&grid("467..67")).unwrap(),
+            vec![vec![
+                (467, vec![Point(0, 0), Point(1, 0), Point(2, 0)]),
+                (67, vec![Point(5, 0), Point(6, 0)]),
+            ]]
+        );
+    }
+
+    #[test]
+    fn test_part_numbers_counted_once() {
+        // the first 35 touches the symbol with both digits, the second 35 and 67 touch nothing
+        assert_eq!(
+            problem_1(&grid("..35..35.\n...*.....\n.....67..")).unwrap(),
+            35
+        );
+        assert_eq!(
+            problem_1(&grid("..35..35.\n...*..*..\n.....67..")).unwrap(),
+            35 + 35 + 67
+        );
+        assert_eq!(problem_1(&grid("467..67\n......#")).unwrap(), 67);
+    }
+
+    #[test]
+    fn test_number_too_large() {
+        let schematic = grid(".............\n123456789012*");
+        for err in [
+            problem_1(&schematic).unwrap_err(),
+            problem_1_naive(&schematic).unwrap_err(),
+            problem_2(&schematic).unwrap_err(),
+        ] {
+            assert!(matches!(
+                err,
+                AocError::Parse { line: 2, column: 1, ref text, .. } if text == "123456789012"
+            ));
+        }
+        assert_eq!(problem_1(&grid("2147483647*")).unwrap(), i32::MAX);
+    }
+
+    #[test]
+    fn test_example_1() {
+        let example =
+            fs::read_to_string("./data/examples/03/problem1.txt").expect("error loading input");
+        assert_eq!(problem_1(&grid(&example)).unwrap(), 4499);
+        assert_eq!(problem_1_naive(&grid(&example)).unwrap(), 4499);
+    }
+
+    #[test]
+    fn test_schematic() {
+        let example =
+            fs::read_to_string("./data/examples/03/problem1.txt").expect("error loading input");
+        let schematic = Schematic::analyse(&grid(&example)).unwrap();
+
+        let part_sum: i32 = schematic.part_numbers().map(|n| n.value).sum();
+        assert_eq!(part_sum, 4499);
+
+        let hashes: Vec<i32> = schematic.adjacent_to('#').map(|n| n.value).collect();
+        assert_eq!(hashes, vec![633]);
+
+        let first = &schematic.symbols[0];
+        assert_eq!((first.symbol, first.position), ('*', Point(3, 1)));
+        let parts: Vec<i32> = schematic.parts_of(first).map(|n| n.value).collect();
+        assert_eq!(parts, vec![467, 35]);
+
+        let busy: Vec<Point> = schematic
+            .symbols
+            .iter()
+            .filter(|s| s.parts.len() > 1)
+            .map(|s| s.position)
+            .collect();
+        assert_eq!(busy, vec![Point(3, 1), Point(5, 8)]);
+
+        let n598 = schematic.numbers.iter().find(|n| n.value == 598).unwrap();
+        let touching: Vec<Point> = schematic.symbols_of(n598).map(|s| s.position).collect();
+        assert_eq!(touching, vec![Point(5, 8), Point(5, 10), Point(8, 10)]);
     }
 
-    // #[test]
-    // fn test_example_1() {
-    //     let example =
-    //         fs::read_to_string("./data/examples/03/problem1.txt").expect("error loading input");
-    //     assert_eq!(problem_1(&example), 4499);
-    // }
+    #[test]
+    fn test_example_2() {
+        let example =
+            fs::read_to_string("./data/examples/03/problem1.txt").expect("error loading input");
+        assert_eq!(problem_2(&grid(&example)).unwrap(), 467835);
+    }
 }
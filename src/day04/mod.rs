@@ -1,12 +1,13 @@
-use rayon::iter::ParallelIterator;
-use rayon::str::ParallelString;
+use crate::error::AocError;
+use crate::solution::Solution;
+use rayon::iter::{IndexedParallelIterator, IntoParallelRefIterator, ParallelIterator};
 use std::cmp::Ordering;
-use std::collections::HashMap;
-use std::hash::Hash;
+use std::iter;
 
+/// A scratchcard. Both lists of numbers are kept sorted, which `matches_merge` relies on.
 #[derive(Debug, Clone, Eq, PartialEq, Hash)]
-struct Card {
-    id: i32,
+pub struct Card {
+    pub id: i32,
     chosen: Vec<i32>,
     winning: Vec<i32>,
 }
@@ -24,124 +25,218 @@ impl Ord for Card {
 }
 
 impl TryFrom<&str> for Card {
-    type Error = String;
+    type Error = AocError;
 
     fn try_from(line: &str) -> Result<Self, Self::Error> {
         let id: &str = line
             .split(':')
             .next()
-            .expect("there is always at aleast one colon");
+            .expect("split always yields at least one item");
         let numbers: &str = line
             .split(':')
             .nth(1)
-            .expect("there is always at aleast one colon");
-        let id: i32 = id
+            .ok_or_else(|| AocError::parse(line, line, "expected a `:` after the card id"))?;
+        let id: &str = id
             .strip_prefix("Card")
-            .expect("id is always prefixed by Card and some whitespace")
-            .trim()
+            .ok_or_else(|| AocError::parse(line, id, "expected a card to start with `Card`"))?
+            .trim();
+        let id: i32 = id
             .parse()
-            .expect("line can always be parsed as i32");
+            .map_err(|_| AocError::parse(line, id, "expected a numeric card id"))?;
         let numbers: Vec<Vec<i32>> = numbers
             .split('|')
             .map(|s| s.trim())
             .map(|s| {
-                let mut nums = s
-                    .split_whitespace()
+                s.split_whitespace()
                     .map(|n: &str| {
                         n.parse::<i32>()
-                            .expect("all entries will be parseable as i32")
+                            .map_err(|_| AocError::parse(line, n, "expected a number"))
                     })
-                    .collect::<Vec<i32>>();
-                nums.sort();
-                nums
+                    .collect::<Result<Vec<i32>, AocError>>()
             })
-            .collect();
+            .collect::<Result<_, AocError>>()?;
         let (chosen, winning) = match &numbers[..] {
             [first, second, ..] => (first.to_owned(), second.to_owned()),
-            _ => return Err("Could not split numbers into two groups.".to_string()),
+            _ => {
+                return Err(AocError::parse(
+                    line,
+                    numbers_text(line),
+                    "could not split numbers into two groups",
+                ))
+            }
         };
 
-        let card = Card {
+        Ok(Card::new(id, chosen, winning))
+    }
+}
+
+fn numbers_text(line: &str) -> &str {
+    line.split(':').nth(1).unwrap_or(line).trim()
+}
+
+/// The numbers in both sorted lists, found by merging them.
+fn common<'a>(chosen: &'a [i32], winning: &'a [i32]) -> impl Iterator<Item = i32> + 'a {
+    let (mut i, mut j) = (0, 0);
+    iter::from_fn(move || {
+        while i < chosen.len() && j < winning.len() {
+            match chosen[i].cmp(&winning[j]) {
+                Ordering::Less => i += 1,
+                Ordering::Greater => j += 1,
+                Ordering::Equal => {
+                    i += 1;
+                    return Some(chosen[i - 1]);
+                }
+            }
+        }
+        None
+    })
+}
+
+fn get_winners(chosen: &[i32], winning: &[i32]) -> Option<Vec<i32>> {
+    let winners: Vec<i32> = common(chosen, winning).collect();
+    match winners.len() {
+        0 => None,
+        _ => Some(winners),
+    }
+}
+
+/// `numbers` as a bitset, or `None` if any of them falls outside `0..128`.
+fn bitset(numbers: &[i32]) -> Option<u128> {
+    numbers.iter().try_fold(0u128, |bits, &n| match n {
+        0..=127 => Some(bits | 1 << n),
+        _ => None,
+    })
+}
+
+impl Card {
+    /// A card with the given numbers, in any order.
+    pub fn new(id: i32, mut chosen: Vec<i32>, mut winning: Vec<i32>) -> Self {
+        chosen.sort();
+        winning.sort();
+        Card {
             id,
             chosen,
             winning,
-        };
+        }
+    }
 
-        Ok(card)
+    /// The chosen numbers, sorted.
+    pub fn chosen(&self) -> &[i32] {
+        &self.chosen
+    }
+
+    /// The winning numbers, sorted.
+    pub fn winning(&self) -> &[i32] {
+        &self.winning
+    }
+
+    /// The chosen numbers that are also winning numbers, in order.
+    pub fn winners(&self) -> Vec<i32> {
+        get_winners(&self.chosen, &self.winning).unwrap_or_default()
     }
-}
 
-fn get_winners(chosen: Vec<i32>, winning: Vec<i32>) -> Option<Vec<i32>> {
-    let mut winners: Vec<i32> = Vec::new();
-    for n in chosen.iter() {
-        if winning.contains(n) {
-            winners.push(*n)
+    /// How many chosen numbers are winning numbers, counting a repeated chosen number each
+    /// time. Puzzle numbers are below 100 and distinct, so this intersects two bitsets,
+    /// falling back to `matches_merge` for larger or repeated chosen numbers.
+    pub fn matches(&self) -> usize {
+        match (bitset(&self.chosen), bitset(&self.winning)) {
+            (Some(chosen), Some(winning)) if chosen.count_ones() as usize == self.chosen.len() => {
+                (chosen & winning).count_ones() as usize
+            }
+            _ => self.matches_merge(),
         }
     }
-    match winners.len() {
-        0 => None,
-        _ => Some(winners),
+
+    /// `matches` by a merge of the two sorted lists.
+    pub fn matches_merge(&self) -> usize {
+        common(&self.chosen, &self.winning).count()
+    }
+
+    /// `matches` scanning `winning` for every chosen number, for benchmarks.
+    pub fn matches_naive(&self) -> usize {
+        self.chosen
+            .iter()
+            .filter(|n| self.winning.contains(n))
+            .count()
+    }
+
+    /// The card's score for part 1: 1 for its first match, doubled for each one after.
+    /// `None` past 64 matches, when the score no longer fits in a `u64`.
+    pub fn points(&self) -> Option<u64> {
+        match self.matches() {
+            0 => Some(0),
+            n => u32::try_from(n - 1).ok().and_then(|n| 1u64.checked_shl(n)),
+        }
     }
 }
 
-pub fn problem_1(cards: &str) -> i32 {
-    let cards: Vec<Card> = cards
-        .par_lines()
-        .map(|c: &str| -> Card { c.try_into().expect("could not parse line as Card") })
-        .collect();
+pub struct Day04;
 
-    let scores: Vec<Option<i32>> = cards
-        .into_iter()
-        .map(|c| get_winners(c.chosen, c.winning))
-        .map(|poss_winners| {
-            poss_winners.map(|winners| {
-                let num_winners = winners.len() as u32;
-                2i32.pow(num_winners - 1)
-            })
-        })
-        .collect();
+impl Solution for Day04 {
+    const DAY: u8 = 4;
+
+    type Parsed = Vec<Card>;
+    type Answer1 = u64;
+    type Answer2 = i32;
+
+    fn parse(input: &str) -> Result<Self::Parsed, AocError> {
+        parse_cards(input)
+    }
+
+    fn part1(cards: &Self::Parsed) -> Result<Self::Answer1, AocError> {
+        problem_1(cards)
+    }
+
+    fn part2(cards: &Self::Parsed) -> Result<Self::Answer2, AocError> {
+        Ok(problem_2(cards))
+    }
+}
+
+pub fn parse_cards(cards: &str) -> Result<Vec<Card>, AocError> {
+    let lines: Vec<&str> = cards.lines().collect();
+    lines
+        .par_iter()
+        .enumerate()
+        .map(|(i, c)| Card::try_from(*c).map_err(|e| e.on_line(i)))
+        .collect()
+}
 
-    scores.into_iter().flatten().sum() // unpacks the Option!
+/// Sequential version of `parse_cards` for benchmarks.
+pub fn parse_cards_sequential(cards: &str) -> Result<Vec<Card>, AocError> {
+    cards
+        .lines()
+        .enumerate()
+        .map(|(i, c)| Card::try_from(c).map_err(|e| e.on_line(i)))
+        .collect()
 }
 
-fn gen_inventory(cards: &[Card]) -> HashMap<Card, i32> {
-    let winning_rosetta: Vec<(Card, i32)> = cards
-        .iter()
-        .map(|c| {
-            let winnings = get_winners(c.clone().chosen, c.clone().winning)
-                .unwrap_or_default()
-                .len() as i32;
-            (c.to_owned(), winnings)
+pub fn problem_1(cards: &[Card]) -> Result<u64, AocError> {
+    cards.iter().try_fold(0u64, |total, card| {
+        let points = card.points().ok_or_else(|| AocError::Overflow {
+            what: format!("the points of card {}", card.id),
+        })?;
+        total.checked_add(points).ok_or_else(|| AocError::Overflow {
+            what: "the total points".to_string(),
         })
-        .collect();
-
-    let mut inventory: HashMap<Card, i32> = cards.iter().cloned().map(|c| (c, 1)).collect();
-    for (card, winnings) in winning_rosetta {
-        if winnings > 0 {
-            let current_amount = *inventory.get(&card).expect("This hashmap is pre-populated");
-
-            let i = card.id as usize - 1;
-            let start = i + 1;
-            let end = i + winnings as usize;
-            for card in &cards[start..=end] {
-                let amount = inventory
-                    .get_mut(card)
-                    .expect("This hashmap is pre-populated");
-                *amount += current_amount;
-            }
+    })
+}
+
+/// How many of each card, by position, we end up with. A card with `n` winners wins a copy
+/// of each of the `n` cards below it in `cards`, whatever their ids, up to the last card.
+fn gen_inventory(cards: &[Card]) -> Vec<i32> {
+    let mut inventory: Vec<i32> = vec![1; cards.len()];
+    for (i, card) in cards.iter().enumerate() {
+        let end = (i + card.matches()).min(cards.len() - 1);
+        for j in i + 1..=end {
+            inventory[j] += inventory[i];
         }
     }
 
     inventory
 }
 
-pub fn problem_2(cards: &str) -> i32 {
-    let cards: Vec<Card> = cards
-        .par_lines()
-        .map(|c: &str| -> Card { c.try_into().expect("could not parse line as Card") })
-        .collect();
-
-    gen_inventory(&cards[..]).values().sum()
+pub fn problem_2(cards: &[Card]) -> i32 {
+    gen_inventory(cards).iter().sum()
 }
 
 #[cfg(test)]
@@ -181,11 +276,11 @@ mod tests {
         let example4: Card = example[3].try_into().unwrap();
         let example5: Card = example[4].try_into().unwrap();
 
-        let example1: Vec<i32> = get_winners(example1.chosen, example1.winning).unwrap();
-        let example2: Vec<i32> = get_winners(example2.chosen, example2.winning).unwrap();
-        let example3: Vec<i32> = get_winners(example3.chosen, example3.winning).unwrap();
-        let example4: Vec<i32> = get_winners(example4.chosen, example4.winning).unwrap();
-        let example5: Option<Vec<i32>> = get_winners(example5.chosen, example5.winning);
+        let example1: Vec<i32> = get_winners(&example1.chosen, &example1.winning).unwrap();
+        let example2: Vec<i32> = get_winners(&example2.chosen, &example2.winning).unwrap();
+        let example3: Vec<i32> = get_winners(&example3.chosen, &example3.winning).unwrap();
+        let example4: Vec<i32> = get_winners(&example4.chosen, &example4.winning).unwrap();
+        let example5: Option<Vec<i32>> = get_winners(&example5.chosen, &example5.winning);
 
         let expected1 = vec![17, 48, 83, 86];
         let expected2 = vec![32, 61];
@@ -200,31 +295,116 @@ mod tests {
         assert_eq!(example5, expected5);
     }
 
+    #[test]
+    fn test_matches() {
+        let cards = parse_cards(
+            &fs::read_to_string("./data/examples/04/problem1.txt").expect("error loading input"),
+        )
+        .unwrap();
+        let matches: Vec<usize> = cards.iter().map(|c| c.matches()).collect();
+        assert_eq!(matches, vec![4, 2, 2, 1, 0, 0]);
+        for card in &cards {
+            assert_eq!(card.matches_merge(), card.matches());
+            assert_eq!(card.matches_naive(), card.matches());
+        }
+        assert_eq!(cards[0].winners(), vec![17, 48, 83, 86]);
+        assert_eq!(cards[0].points(), Some(8));
+
+        // numbers too large for the bitset take the merge instead
+        let big = Card::try_from("Card 1: 5 200 1000 | 200 1000 3000").unwrap();
+        assert_eq!(big.matches(), 2);
+
+        // a repeated chosen number matches each time, however it is counted
+        let repeated = Card::try_from("Card 1: 5 5 7 | 5 9").unwrap();
+        assert_eq!(repeated.matches(), 2);
+        assert_eq!(repeated.matches_merge(), 2);
+        assert_eq!(repeated.matches_naive(), 2);
+        assert_eq!(problem_1(&[repeated]).unwrap(), 2);
+
+        // cards built by hand are sorted too, so the merge still lines up
+        let unsorted = Card::new(1, vec![200, 9, 3], vec![3, 200, 9, 1]);
+        assert_eq!(unsorted.chosen(), &[3, 9, 200]);
+        assert_eq!(unsorted.winning(), &[1, 3, 9, 200]);
+        assert_eq!(unsorted.matches(), 3);
+        assert_eq!(unsorted.matches_merge(), 3);
+    }
+
+    #[test]
+    fn test_points_overflow() {
+        let card = |id: i32, matches: i32| {
+            let numbers: Vec<i32> = (1..=matches).collect();
+            Card::new(id, numbers.clone(), numbers)
+        };
+        assert_eq!(card(1, 40).points(), Some(1 << 39));
+        assert_eq!(problem_1(&[card(1, 40)]).unwrap(), 1 << 39);
+        assert_eq!(card(1, 64).points(), Some(1 << 63));
+
+        let err = problem_1(&[card(1, 3), card(2, 65)]).unwrap_err();
+        assert_eq!(err.to_string(), "the points of card 2 overflowed");
+        let err = problem_1(&[card(1, 64), card(2, 64)]).unwrap_err();
+        assert_eq!(err.to_string(), "the total points overflowed");
+    }
+
     #[test]
     fn test_find_copies() {
         let example =
             fs::read_to_string("./data/examples/04/problem1.txt").expect("error loading input");
         let example: Vec<Card> = example.lines().map(|s| s.try_into().unwrap()).collect();
-        let inventory: HashMap<Card, i32> = gen_inventory(&example[..]);
-        let mut ids: Vec<(i32, i32)> = inventory.into_iter().map(|(c, a)| (c.id, a)).collect();
-        ids.sort();
+        let inventory: Vec<i32> = gen_inventory(&example[..]);
+        let ids: Vec<(i32, i32)> = example.iter().map(|c| c.id).zip(inventory).collect();
 
         let expected: Vec<(i32, i32)> = vec![(1, 1), (2, 2), (3, 4), (4, 8), (5, 14), (6, 1)];
 
         assert_eq!(ids, expected);
     }
 
+    #[test]
+    fn test_copies_by_position() {
+        // ids out of order and with gaps; the last card's winnings run past the end
+        let cards =
+            parse_cards("Card 9: 1 2 | 1 2\nCard 3: 5 | 5\nCard 40: 7 | 8\nCard 2: 4 6 | 4 6")
+                .unwrap();
+        assert_eq!(gen_inventory(&cards), vec![1, 2, 4, 1]);
+        assert_eq!(problem_2(&cards), 8);
+        assert_eq!(problem_2(&[]), 0);
+    }
+
     #[test]
     fn test_example_1() {
         let example =
             fs::read_to_string("./data/examples/04/problem1.txt").expect("error loading input");
-        assert_eq!(problem_1(&example), 13);
+        assert_eq!(problem_1(&parse_cards(&example).unwrap()).unwrap(), 13);
     }
 
     #[test]
     fn test_example_2() {
         let example =
             fs::read_to_string("./data/examples/04/problem1.txt").expect("error loading input");
-        assert_eq!(problem_2(&example), 30);
+        assert_eq!(problem_2(&parse_cards(&example).unwrap()), 30);
+    }
+
+    #[test]
+    fn test_parse_sequential() {
+        let example =
+            fs::read_to_string("./data/examples/04/problem1.txt").expect("error loading input");
+        assert_eq!(
+            parse_cards_sequential(&example).unwrap(),
+            parse_cards(&example).unwrap()
+        );
+    }
+
+    #[test]
+    fn test_parse_errors() {
+        let err = parse_cards("Card 1: 1 2 | 3 4\nCard 2: 1 x | 3 4").unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "line 2, column 11: expected a number: \"x\""
+        );
+
+        let err = Card::try_from("Card 3: 1 2 3").unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "line 1, column 9: could not split numbers into two groups: \"1 2 3\""
+        );
     }
 }
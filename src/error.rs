@@ -0,0 +1,82 @@
+use crate::input::InputError;
+use thiserror::Error;
+
+#[derive(Debug, Error)]
+pub enum AocError {
+    #[error("line {line}, column {column}: {reason}: {text:?}")]
+    Parse {
+        line: usize,
+        column: usize,
+        text: String,
+        reason: String,
+    },
+    #[error(transparent)]
+    Input(#[from] InputError),
+    #[error("parsed input handed to day {day} came from another day")]
+    WrongDay { day: u8 },
+    /// An answer, or a step towards one, too large for the type it is computed in.
+    #[error("{what} overflowed")]
+    Overflow { what: String },
+}
+
+impl AocError {
+    /// A parse error for `text`, which should be a slice of `src` so the column can be worked out.
+    /// Lines and columns are 1-based; the line defaults to 1 until `on_line` says otherwise.
+    pub fn parse(src: &str, text: &str, reason: impl Into<String>) -> Self {
+        AocError::Parse {
+            line: 1,
+            column: offset_of(src, text) + 1,
+            text: text.to_string(),
+            reason: reason.into(),
+        }
+    }
+
+    /// Sets the line number of a parse error, given the 0-based index from `lines().enumerate()`.
+    pub fn on_line(mut self, index: usize) -> Self {
+        if let AocError::Parse { ref mut line, .. } = self {
+            *line = index + 1;
+        }
+        self
+    }
+
+    /// Re-bases the column of an error raised while parsing `inner`, a slice of `outer`.
+    pub fn within(mut self, outer: &str, inner: &str) -> Self {
+        if let AocError::Parse { ref mut column, .. } = self {
+            *column += offset_of(outer, inner);
+        }
+        self
+    }
+}
+
+fn offset_of(outer: &str, inner: &str) -> usize {
+    let start = outer.as_ptr() as usize;
+    let end = start + outer.len();
+    match inner.as_ptr() as usize {
+        p if p >= start && p + inner.len() <= end => p - start,
+        _ => 0,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_position() {
+        let line = "Game 1: 3 blue";
+        let inner = &line[8..];
+        let err = AocError::parse(inner, &inner[2..], "bad colour")
+            .within(line, inner)
+            .on_line(4);
+        assert_eq!(
+            err.to_string(),
+            "line 5, column 11: bad colour: \"blue\"".to_string()
+        );
+    }
+
+    #[test]
+    fn test_unrelated_text() {
+        let err = AocError::parse("abc", "xyz", "oops");
+        assert!(matches!(err, AocError::Parse { column: 1, .. }));
+    }
+}
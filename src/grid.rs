@@ -0,0 +1,237 @@
+use crate::error::AocError;
+use std::fmt;
+
+/// A position in a `Grid`, as `(x, y)` with `y` counting rows down from the top.
+/// Coordinates are signed so neighbours can be computed before bounds checking.
+#[derive(Debug, Eq, PartialEq, Copy, Clone, PartialOrd, Ord, Hash)]
+pub struct Point(pub i32, pub i32);
+
+impl Point {
+    #[rustfmt::skip]
+    const NEIGHBOURS_4: [(i32, i32); 4] = [
+                  (0, -1),
+        (-1, 0),           (1, 0),
+                  (0, 1),
+    ];
+
+    #[rustfmt::skip]
+    const NEIGHBOURS_8: [(i32, i32); 8] = [
+        (-1, -1), (0, -1), (1, -1),
+        (-1, 0),           (1, 0),
+        (-1, 1),  (0, 1),  (1, 1),
+    ];
+
+    fn offset(self, (dx, dy): (i32, i32)) -> Point {
+        Point(self.0 + dx, self.1 + dy)
+    }
+}
+
+/// A rectangular grid stored row by row.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Grid<T> {
+    width: usize,
+    height: usize,
+    cells: Vec<T>,
+}
+
+impl<T> Grid<T> {
+    /// Parses one cell per char, one row per line. Every line must be the same width.
+    pub fn parse(text: &str, mut cell: impl FnMut(char) -> T) -> Result<Self, AocError> {
+        let mut width: Option<usize> = None;
+        let mut height = 0;
+        let mut cells: Vec<T> = Vec::new();
+        for (y, line) in text.lines().enumerate() {
+            let len = line.chars().count();
+            match width {
+                Some(w) if w != len => {
+                    return Err(AocError::parse(
+                        line,
+                        line,
+                        format!("expected a row {w} cells wide, found {len}"),
+                    )
+                    .on_line(y))
+                }
+                _ => width = Some(len),
+            }
+            cells.extend(line.chars().map(&mut cell));
+            height += 1;
+        }
+
+        Ok(Grid {
+            width: width.unwrap_or(0),
+            height,
+            cells,
+        })
+    }
+
+    /// A `width` by `height` grid with every cell set to `value`.
+    pub fn filled(width: usize, height: usize, value: T) -> Self
+    where
+        T: Clone,
+    {
+        Grid {
+            width,
+            height,
+            cells: vec![value; width * height],
+        }
+    }
+
+    pub fn width(&self) -> usize {
+        self.width
+    }
+
+    pub fn height(&self) -> usize {
+        self.height
+    }
+
+    pub fn in_bounds(&self, p: Point) -> bool {
+        (0..self.width as i32).contains(&p.0) && (0..self.height as i32).contains(&p.1)
+    }
+
+    fn index(&self, p: Point) -> Option<usize> {
+        match self.in_bounds(p) {
+            true => Some(p.1 as usize * self.width + p.0 as usize),
+            false => None,
+        }
+    }
+
+    pub fn get(&self, p: Point) -> Option<&T> {
+        self.index(p).map(|i| &self.cells[i])
+    }
+
+    pub fn get_mut(&mut self, p: Point) -> Option<&mut T> {
+        self.index(p).map(|i| &mut self.cells[i])
+    }
+
+    /// The in-bounds orthogonal neighbours of `p`.
+    pub fn neighbours_4(&self, p: Point) -> impl Iterator<Item = Point> + '_ {
+        Point::NEIGHBOURS_4
+            .into_iter()
+            .map(move |d| p.offset(d))
+            .filter(|n| self.in_bounds(*n))
+    }
+
+    /// The in-bounds orthogonal and diagonal neighbours of `p`.
+    pub fn neighbours_8(&self, p: Point) -> impl Iterator<Item = Point> + '_ {
+        Point::NEIGHBOURS_8
+            .into_iter()
+            .map(move |d| p.offset(d))
+            .filter(|n| self.in_bounds(*n))
+    }
+
+    /// Every point in the grid, row by row.
+    pub fn points(&self) -> impl Iterator<Item = Point> {
+        let (width, height) = (self.width as i32, self.height as i32);
+        (0..height).flat_map(move |y| (0..width).map(move |x| Point(x, y)))
+    }
+
+    pub fn rows(&self) -> impl Iterator<Item = &[T]> {
+        // `chunks` panics on a zero width, which only happens when there are no cells anyway
+        self.cells.chunks(self.width.max(1))
+    }
+
+    pub fn column(&self, x: usize) -> impl Iterator<Item = &T> {
+        self.rows().filter_map(move |row| row.get(x))
+    }
+
+    pub fn columns(&self) -> impl Iterator<Item = impl Iterator<Item = &T>> {
+        (0..self.width).map(|x| self.column(x))
+    }
+
+    /// Every point whose cell matches `predicate`, row by row.
+    pub fn find_all(&self, predicate: impl Fn(&T) -> bool) -> Vec<Point> {
+        self.points()
+            .zip(self.cells.iter())
+            .filter(|(_, cell)| predicate(cell))
+            .map(|(p, _)| p)
+            .collect()
+    }
+}
+
+impl TryFrom<&str> for Grid<char> {
+    type Error = AocError;
+
+    fn try_from(text: &str) -> Result<Self, Self::Error> {
+        Grid::parse(text, |c| c)
+    }
+}
+
+impl<T: fmt::Display> fmt::Display for Grid<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for (y, row) in self.rows().enumerate() {
+            if y > 0 {
+                writeln!(f)?;
+            }
+            for cell in row {
+                write!(f, "{cell}")?;
+            }
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn example() -> Grid<char> {
+        "ab.\n.c.\n..d".try_into().unwrap()
+    }
+
+    #[test]
+    fn test_parse() {
+        let grid = example();
+        assert_eq!((grid.width(), grid.height()), (3, 3));
+        assert_eq!(grid.get(Point(1, 1)), Some(&'c'));
+        assert_eq!(grid.get(Point(3, 0)), None);
+        assert_eq!(grid.get(Point(0, -1)), None);
+        assert_eq!(grid.to_string(), "ab.\n.c.\n..d");
+
+        let err = Grid::try_from("abc\nab").unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "line 2, column 1: expected a row 3 cells wide, found 2: \"ab\""
+        );
+    }
+
+    #[test]
+    fn test_neighbours() {
+        let grid = example();
+        assert_eq!(
+            grid.neighbours_4(Point(0, 0)).collect::<Vec<Point>>(),
+            vec![Point(1, 0), Point(0, 1)]
+        );
+        assert_eq!(grid.neighbours_8(Point(1, 1)).count(), 8);
+        assert_eq!(
+            grid.neighbours_8(Point(2, 2)).collect::<Vec<Point>>(),
+            vec![Point(1, 1), Point(2, 1), Point(1, 2)]
+        );
+    }
+
+    #[test]
+    fn test_rows_and_columns() {
+        let grid = example();
+        let rows: Vec<String> = grid.rows().map(|r| r.iter().collect()).collect();
+        let columns: Vec<String> = grid.columns().map(|c| c.collect()).collect();
+        assert_eq!(rows, vec!["ab.", ".c.", "..d"]);
+        assert_eq!(columns, vec!["a..", "bc.", "..d"]);
+    }
+
+    #[test]
+    fn test_find_all() {
+        let mut grid = example();
+        assert_eq!(
+            grid.find_all(|c| *c != '.'),
+            vec![Point(0, 0), Point(1, 0), Point(1, 1), Point(2, 2)]
+        );
+        *grid.get_mut(Point(2, 0)).unwrap() = 'x';
+        assert_eq!(grid.find_all(|c| *c == 'x'), vec![Point(2, 0)]);
+    }
+
+    #[test]
+    fn test_filled() {
+        let grid = Grid::filled(2, 3, '#');
+        assert_eq!((grid.width(), grid.height()), (2, 3));
+        assert_eq!(grid.to_string(), "##\n##\n##");
+    }
+}
@@ -0,0 +1,169 @@
+use crate::answers::Answers;
+use serde::de::DeserializeOwned;
+use std::fs;
+use std::io::{self, Read};
+use std::path::{Path, PathBuf};
+use thiserror::Error;
+
+const DEFAULT_DATA_DIR: &str = concat!(env!("CARGO_MANIFEST_DIR"), "/data");
+
+#[derive(Debug, Error)]
+pub enum InputError {
+    #[error("day {0} is not an Advent of Code day, expected 1 to 25")]
+    InvalidDay(u8),
+    #[error("could not read {}: {source}", path.display())]
+    Io { path: PathBuf, source: io::Error },
+    #[error("{} is not valid UTF-8", path.display())]
+    NotUtf8 { path: PathBuf },
+    #[error("{} is empty", path.display())]
+    Empty { path: PathBuf },
+    #[error("could not parse answers in {}: {source}", path.display())]
+    Answers {
+        path: PathBuf,
+        source: Box<toml::de::Error>,
+    },
+    #[error("could not parse {}: {source}", path.display())]
+    Config {
+        path: PathBuf,
+        source: Box<toml::de::Error>,
+    },
+}
+
+/// Finds puzzle inputs and known answers under a data directory laid out as `input/NN.txt`
+/// and `answers/NN.toml`.
+#[derive(Debug, Clone)]
+pub struct Loader {
+    data_dir: PathBuf,
+}
+
+impl Loader {
+    /// Uses `data_dir` if given, otherwise the `data` directory of this crate.
+    pub fn new(data_dir: Option<PathBuf>) -> Self {
+        let data_dir = data_dir.unwrap_or_else(|| PathBuf::from(DEFAULT_DATA_DIR));
+        Loader { data_dir }
+    }
+
+    fn day_path(&self, dir: &str, day: u8, ext: &str) -> Result<PathBuf, InputError> {
+        if !(1..=25).contains(&day) {
+            return Err(InputError::InvalidDay(day));
+        }
+        Ok(self.data_dir.join(dir).join(format!("{:0>2}.{ext}", day)))
+    }
+
+    pub fn input_path(&self, day: u8) -> Result<PathBuf, InputError> {
+        self.day_path("input", day, "txt")
+    }
+
+    pub fn answers_path(&self, day: u8) -> Result<PathBuf, InputError> {
+        self.day_path("answers", day, "toml")
+    }
+
+    pub fn load_day(&self, day: u8) -> Result<String, InputError> {
+        load_file(&self.input_path(day)?)
+    }
+
+    /// Reads the known answers for `day`, with no answers at all if the file does not exist.
+    pub fn load_answers(&self, day: u8) -> Result<Answers, InputError> {
+        let path = self.answers_path(day)?;
+        if !path.exists() {
+            return Ok(Answers::default());
+        }
+        let answers = fs::read_to_string(&path).map_err(|source| InputError::Io {
+            path: path.clone(),
+            source,
+        })?;
+        toml::from_str(&answers).map_err(|source| InputError::Answers {
+            path,
+            source: Box::new(source),
+        })
+    }
+}
+
+/// Reads an input file, or stdin when `path` is `-`.
+pub fn load_file(path: &Path) -> Result<String, InputError> {
+    let mut bytes = Vec::new();
+    let (path, read) = if path == Path::new("-") {
+        let read = io::stdin().read_to_end(&mut bytes).map(|_| ());
+        (Path::new("<stdin>"), read)
+    } else {
+        (path, fs::read(path).map(|b| bytes = b))
+    };
+    read.map_err(|source| InputError::Io {
+        path: path.to_path_buf(),
+        source,
+    })?;
+
+    let input = String::from_utf8(bytes).map_err(|_| InputError::NotUtf8 {
+        path: path.to_path_buf(),
+    })?;
+    match input.trim().is_empty() {
+        true => Err(InputError::Empty {
+            path: path.to_path_buf(),
+        }),
+        false => Ok(input),
+    }
+}
+
+/// Reads a TOML configuration file, such as the bags to check day 2 against.
+pub fn load_config<T: DeserializeOwned>(path: &Path) -> Result<T, InputError> {
+    let config = load_file(path)?;
+    toml::from_str(&config).map_err(|source| InputError::Config {
+        path: path.to_path_buf(),
+        source: Box::new(source),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::env;
+
+    #[test]
+    fn test_default_data_dir() {
+        let loader = Loader::new(None);
+        assert!(loader.load_day(1).is_ok());
+    }
+
+    #[test]
+    fn test_load_answers() {
+        let loader = Loader::new(None);
+        assert!(loader.load_answers(1).unwrap().expected(1).is_some());
+        assert_eq!(loader.load_answers(25).unwrap(), Answers::default());
+    }
+
+    #[test]
+    fn test_invalid_day() {
+        let loader = Loader::new(None);
+        assert!(matches!(loader.load_day(0), Err(InputError::InvalidDay(0))));
+        assert!(matches!(
+            loader.load_day(26),
+            Err(InputError::InvalidDay(26))
+        ));
+    }
+
+    #[test]
+    fn test_missing_file_reports_path() {
+        let loader = Loader::new(Some(PathBuf::from("./does-not-exist")));
+        let err = loader.load_day(1).unwrap_err();
+        assert!(matches!(err, InputError::Io { .. }));
+        assert!(err.to_string().contains("01.txt"));
+    }
+
+    #[test]
+    fn test_empty_and_non_utf8() {
+        let dir = env::temp_dir().join(format!("aoc2023-input-{}", std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        let empty = dir.join("empty.txt");
+        let binary = dir.join("binary.txt");
+        fs::write(&empty, "\n\n").unwrap();
+        fs::write(&binary, [0xff, 0xfe, 0x00]).unwrap();
+
+        assert!(matches!(load_file(&empty), Err(InputError::Empty { .. })));
+        assert!(matches!(
+            load_file(&binary),
+            Err(InputError::NotUtf8 { .. })
+        ));
+
+        fs::remove_dir_all(dir).unwrap();
+    }
+}
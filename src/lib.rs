@@ -0,0 +1,19 @@
+pub mod answers;
+pub mod day01;
+pub mod day02;
+pub mod day03;
+pub mod day04;
+pub mod error;
+pub mod grid;
+pub mod input;
+pub mod solution;
+
+pub use error::AocError;
+pub use solution::{Runner, Solution};
+
+/// Every implemented day, in order. Adding a day is one line here.
+pub static DAYS: &[&dyn Runner] = &[&day01::Day01, &day02::Day02, &day03::Day03, &day04::Day04];
+
+pub fn find_day(day: u8) -> Option<&'static dyn Runner> {
+    DAYS.iter().find(|d| d.day() == day).copied()
+}
@@ -1,28 +1,280 @@
-use std::fs;
-
-mod day01;
-mod day02;
-// mod day03;
-mod day04;
-
-fn main() {
-    let day1_1 = day01::problem_1(read_input(1));
-    let day1_2 = day01::problem_2(read_input(1));
-    let day2_1 = day02::problem_1(read_input(2));
-    let day2_2 = day02::problem_2(read_input(2));
-    // let day3_1 = day03::problem_1(&read_input(3));
-    let day4_1 = day04::problem_1(&read_input(4));
-    let day4_2 = day04::problem_2(&read_input(4));
-
-    println!("Day 1, problem 1: {day1_1}");
-    println!("Day 1, problem 2: {day1_2}");
-    println!("Day 2, problem 1: {day2_1}");
-    println!("Day 1, problem 2: {day2_2}");
-    // println!("{day3_1}");
-    println!("Day 4, problem 1: {day4_1}");
-    println!("Day 4, problem 2: {day4_2}");
-}
-
-fn read_input(day: i32) -> String {
-    fs::read_to_string(format!("./data/input/{:0>2}.txt", day)).expect("error loading input")
+use aoc2023::answers::{Answers, Status};
+use aoc2023::day01::{self, DigitVocabulary};
+use aoc2023::day02::{self, BagConfig, PossibleBags, Set};
+use aoc2023::input::{self, Loader};
+use aoc2023::{AocError, Runner, Solution, DAYS};
+use clap::Parser;
+use cli::{BagsArgs, CalibrateArgs, Cli, Command, Format, RunArgs, VerifyArgs};
+use report::{timed, CalibrationRecord, DayReport, GameBags, PartReport, Record, RecordStatus};
+use std::process::ExitCode;
+
+mod cli;
+mod report;
+
+fn main() -> ExitCode {
+    let cli = Cli::parse();
+    let loader = Loader::new(cli.data_dir);
+    match cli.command {
+        Some(Command::Run(args)) => run_days(&loader, &args, cli.format),
+        Some(Command::Verify(args)) => verify_days(&loader, &args, cli.format),
+        Some(Command::Bags(args)) => check_bags(&loader, &args, cli.format),
+        Some(Command::Calibrate(args)) => calibrate(&loader, &args, cli.format),
+        None => run_days(&loader, &RunArgs::default(), cli.format),
+    }
+}
+
+fn select_days(day: Option<u8>) -> Option<Vec<&'static dyn Runner>> {
+    match day {
+        Some(n) => match aoc2023::find_day(n) {
+            Some(day) => Some(vec![day]),
+            None => {
+                eprintln!("Day {n} is not implemented");
+                None
+            }
+        },
+        None => Some(DAYS.to_vec()),
+    }
+}
+
+fn run_days(loader: &Loader, args: &RunArgs, format: Format) -> ExitCode {
+    let Some(days) = select_days(args.day) else {
+        return ExitCode::FAILURE;
+    };
+
+    let mut reports = Vec::new();
+    let mut records = Vec::new();
+    let mut failed = false;
+    for day in days {
+        match run(day, loader, args) {
+            Ok(report) => {
+                for part in &report.parts {
+                    match (&part.answer, format) {
+                        (Ok(answer), Format::Text) => {
+                            println!("Day {}, problem {}: {answer}", report.day, part.part)
+                        }
+                        (Ok(_), Format::Json) => {
+                            records.push(Record::new(report.day, part, RecordStatus::Ok))
+                        }
+                        (Err(e), Format::Text) => {
+                            eprintln!("error: day {}, problem {}: {e}", report.day, part.part)
+                        }
+                        (Err(e), Format::Json) => {
+                            records.push(Record::error(report.day, part.part, e))
+                        }
+                    }
+                    failed |= part.answer.is_err();
+                }
+                reports.push(report);
+            }
+            Err(e) => {
+                let parts = [1, 2].into_iter().filter(|p| args.runs_part(*p));
+                match format {
+                    Format::Text => eprintln!("error: day {}: {e}", day.day()),
+                    Format::Json => records.extend(parts.map(|p| Record::error(day.day(), p, &e))),
+                }
+                failed = true;
+            }
+        }
+    }
+
+    match format {
+        Format::Text if !reports.is_empty() => report::print_timings(&reports),
+        Format::Text => {}
+        Format::Json => report::print_json(&records),
+    }
+    exit_code(!failed)
+}
+
+fn verify_days(loader: &Loader, args: &VerifyArgs, format: Format) -> ExitCode {
+    let Some(days) = select_days(args.day) else {
+        return ExitCode::FAILURE;
+    };
+
+    let mut passed = true;
+    let mut records = Vec::new();
+    for day in days {
+        match verify(day, loader) {
+            Ok((answers, report)) => {
+                for part in &report.parts {
+                    let answer = match &part.answer {
+                        Ok(answer) => answer,
+                        Err(e) => {
+                            passed = false;
+                            match format {
+                                Format::Text => {
+                                    eprintln!(
+                                        "error: day {}, problem {}: {e}",
+                                        day.day(),
+                                        part.part
+                                    )
+                                }
+                                Format::Json => {
+                                    records.push(Record::error(day.day(), part.part, e))
+                                }
+                            }
+                            continue;
+                        }
+                    };
+                    let status = answers.check(part.part, answer);
+                    passed &= !matches!(status, Status::Fail { .. });
+                    match format {
+                        Format::Text => print_status(day.day(), part.part, answer, &status),
+                        Format::Json => {
+                            records.push(Record::new(day.day(), part, (&status).into()))
+                        }
+                    }
+                }
+            }
+            Err(e) => {
+                passed = false;
+                match format {
+                    Format::Text => eprintln!("error: day {}: {e}", day.day()),
+                    Format::Json => records.extend([1, 2].map(|p| Record::error(day.day(), p, &e))),
+                }
+            }
+        }
+    }
+
+    if format == Format::Json {
+        report::print_json(&records);
+    }
+    exit_code(passed)
+}
+
+fn check_bags(loader: &Loader, args: &BagsArgs, format: Format) -> ExitCode {
+    match possible_bags(loader, args) {
+        Ok((bags, games)) => {
+            match format {
+                Format::Text => report::print_bags(&bags, &games),
+                Format::Json => {
+                    let records: Vec<GameBags> = games
+                        .iter()
+                        .map(|(id, fits)| GameBags::new(*id, fits))
+                        .collect();
+                    report::print_json(&records)
+                }
+            }
+            ExitCode::SUCCESS
+        }
+        Err(e) => {
+            eprintln!("error: day {}: {e}", day02::Day02::DAY);
+            ExitCode::FAILURE
+        }
+    }
+}
+
+/// The bags to check, falling back to the puzzle's own, and the games that fit each of them.
+fn possible_bags(
+    loader: &Loader,
+    args: &BagsArgs,
+) -> Result<(Vec<Set>, Vec<PossibleBags>), AocError> {
+    let mut bags = args.bags.clone();
+    if let Some(path) = &args.config {
+        let config: BagConfig = input::load_config(path)?;
+        bags.extend(config.sets()?);
+    }
+    if bags.is_empty() {
+        bags.push(day02::bag_1());
+    }
+
+    let input = match &args.input {
+        Some(path) => input::load_file(path)?,
+        None => loader.load_day(day02::Day02::DAY)?,
+    };
+    let games = day02::parse_games(&input)?;
+    let possible = day02::possible_bags(&games, &bags);
+    Ok((bags, possible))
+}
+
+fn calibrate(loader: &Loader, args: &CalibrateArgs, format: Format) -> ExitCode {
+    match calibration_records(loader, args) {
+        Ok(records) => {
+            match format {
+                Format::Text => records.iter().for_each(report::print_calibration),
+                Format::Json => report::print_json(&records),
+            }
+            ExitCode::SUCCESS
+        }
+        Err(e) => {
+            eprintln!("error: day {}: {e}", day01::Day01::DAY);
+            ExitCode::FAILURE
+        }
+    }
+}
+
+fn calibration_records(
+    loader: &Loader,
+    args: &CalibrateArgs,
+) -> Result<Vec<CalibrationRecord>, AocError> {
+    let input = match &args.input {
+        Some(path) => input::load_file(path)?,
+        None => loader.load_day(day01::Day01::DAY)?,
+    };
+
+    let mut records = Vec::new();
+    if args.runs_part(1) {
+        let report = day01::problem_1_with(&input, args.missing_digits)?;
+        records.push(CalibrationRecord::new(1, report));
+    }
+    if args.runs_part(2) {
+        let english = DigitVocabulary::english();
+        let report = day01::calibrate_with(&input, &english, args.missing_digits)?;
+        records.push(CalibrationRecord::new(2, report));
+    }
+    Ok(records)
+}
+
+fn exit_code(success: bool) -> ExitCode {
+    match success {
+        true => ExitCode::SUCCESS,
+        false => ExitCode::FAILURE,
+    }
+}
+
+fn run(day: &dyn Runner, loader: &Loader, args: &RunArgs) -> Result<DayReport, AocError> {
+    let (input, read) = timed(|| match &args.input {
+        Some(path) => input::load_file(path),
+        None => loader.load_day(day.day()),
+    });
+    let (parsed, parse) = timed(|| day.parse_any(&input?));
+    let parsed = parsed?;
+
+    let mut parts = Vec::new();
+    for part in [1, 2] {
+        if !args.runs_part(part) {
+            continue;
+        }
+        let (answer, elapsed) = timed(|| match part {
+            1 => day.run_part1(&*parsed),
+            _ => day.run_part2(&*parsed),
+        });
+        parts.push(PartReport {
+            part,
+            answer,
+            elapsed,
+        });
+    }
+
+    Ok(DayReport {
+        day: day.day(),
+        read,
+        parse,
+        parts,
+    })
+}
+
+/// The day's expected answers and a full run of it, each part carrying its own result.
+fn verify(day: &dyn Runner, loader: &Loader) -> Result<(Answers, DayReport), AocError> {
+    let answers = loader.load_answers(day.day())?;
+    let report = run(day, loader, &RunArgs::default())?;
+    Ok((answers, report))
+}
+
+fn print_status(day: u8, part: u8, answer: &str, status: &Status) {
+    match status {
+        Status::Fail { expected } => {
+            println!("Day {day}, problem {part}: {status} (expected {expected}, got {answer})")
+        }
+        _ => println!("Day {day}, problem {part}: {status} ({answer})"),
+    }
 }
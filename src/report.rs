@@ -0,0 +1,197 @@
+use aoc2023::answers::Status;
+use aoc2023::day01::CalibrationReport;
+use aoc2023::day02::{PossibleBags, Set};
+use aoc2023::AocError;
+use serde::Serialize;
+use std::time::{Duration, Instant};
+
+#[derive(Debug)]
+pub struct PartReport {
+    pub part: u8,
+    /// The part's own result, so one failing part does not hide the other's answer.
+    pub answer: Result<String, AocError>,
+    pub elapsed: Duration,
+}
+
+/// Wall-clock time spent on each phase of one day's run.
+#[derive(Debug)]
+pub struct DayReport {
+    pub day: u8,
+    pub read: Duration,
+    pub parse: Duration,
+    pub parts: Vec<PartReport>,
+}
+
+impl DayReport {
+    pub fn part(&self, part: u8) -> Option<&PartReport> {
+        self.parts.iter().find(|p| p.part == part)
+    }
+
+    pub fn total(&self) -> Duration {
+        self.read + self.parse + self.parts.iter().map(|p| p.elapsed).sum::<Duration>()
+    }
+}
+
+pub fn timed<T>(f: impl FnOnce() -> T) -> (T, Duration) {
+    let start = Instant::now();
+    let result = f();
+    (result, start.elapsed())
+}
+
+pub fn print_timings(reports: &[DayReport]) {
+    println!();
+    println!(
+        "{:>5} {:>12} {:>12} {:>12} {:>12} {:>12}",
+        "Day", "Read", "Parse", "Part 1", "Part 2", "Total"
+    );
+    for r in reports {
+        let part = |n: u8| r.part(n).map_or("-".to_string(), |p| fmt(p.elapsed));
+        println!(
+            "{:>5} {:>12} {:>12} {:>12} {:>12} {:>12}",
+            r.day,
+            fmt(r.read),
+            fmt(r.parse),
+            part(1),
+            part(2),
+            fmt(r.total())
+        );
+    }
+    let total: Duration = reports.iter().map(|r| r.total()).sum();
+    println!("{:>5} {:>64}", "Total", fmt(total));
+}
+
+fn fmt(d: Duration) -> String {
+    format!("{:.2?}", d)
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum RecordStatus {
+    Ok,
+    Error,
+    Pass,
+    Fail,
+    Missing,
+}
+
+impl From<&Status> for RecordStatus {
+    fn from(status: &Status) -> Self {
+        match status {
+            Status::Pass => RecordStatus::Pass,
+            Status::Fail { .. } => RecordStatus::Fail,
+            Status::Missing => RecordStatus::Missing,
+        }
+    }
+}
+
+/// One line of `--format json` output.
+#[derive(Debug, Serialize)]
+pub struct Record {
+    pub day: u8,
+    pub part: u8,
+    pub answer: Option<String>,
+    pub duration_ns: u128,
+    pub status: RecordStatus,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+}
+
+impl Record {
+    pub fn new(day: u8, part: &PartReport, status: RecordStatus) -> Self {
+        Record {
+            day,
+            part: part.part,
+            answer: part.answer.as_ref().ok().cloned(),
+            duration_ns: part.elapsed.as_nanos(),
+            status,
+            error: None,
+        }
+    }
+
+    pub fn error(day: u8, part: u8, error: &AocError) -> Self {
+        Record {
+            day,
+            part,
+            answer: None,
+            duration_ns: 0,
+            status: RecordStatus::Error,
+            error: Some(error.to_string()),
+        }
+    }
+}
+
+/// One part of a day 1 run under a missing-digit policy, and the lines the policy affected.
+#[derive(Debug, Serialize)]
+pub struct CalibrationRecord {
+    pub part: u8,
+    pub total: u32,
+    pub skipped: Vec<usize>,
+    pub zeroed: Vec<usize>,
+}
+
+impl CalibrationRecord {
+    pub fn new(part: u8, report: CalibrationReport) -> Self {
+        CalibrationRecord {
+            part,
+            total: report.total,
+            skipped: report.skipped,
+            zeroed: report.zeroed,
+        }
+    }
+}
+
+pub fn print_calibration(record: &CalibrationRecord) {
+    let lines = |lines: &[usize]| -> String {
+        let lines: Vec<String> = lines.iter().map(|l| l.to_string()).collect();
+        lines.join(", ")
+    };
+    println!("Day 1, problem {}: {}", record.part, record.total);
+    if !record.skipped.is_empty() {
+        println!("  skipped lines: {}", lines(&record.skipped));
+    }
+    if !record.zeroed.is_empty() {
+        println!("  zeroed lines: {}", lines(&record.zeroed));
+    }
+}
+
+/// The bags one day 2 game could have been played with.
+#[derive(Debug, Serialize)]
+pub struct GameBags {
+    pub id: i32,
+    pub bags: Vec<String>,
+}
+
+impl GameBags {
+    pub fn new(id: i32, bags: &[Set]) -> Self {
+        GameBags {
+            id,
+            bags: bags.iter().map(|b| b.to_string()).collect(),
+        }
+    }
+}
+
+/// Prints the sum of possible game ids for each bag, then the bags each game fits.
+pub fn print_bags(bags: &[Set], games: &[PossibleBags]) {
+    for bag in bags {
+        let sum: i32 = games
+            .iter()
+            .filter(|(_, fits)| fits.contains(bag))
+            .map(|(id, _)| id)
+            .sum();
+        println!("Bag {bag}: {sum}");
+    }
+    for (id, fits) in games {
+        let fits: Vec<String> = fits.iter().map(|b| b.to_string()).collect();
+        match fits.is_empty() {
+            true => println!("Game {id}: no bag"),
+            false => println!("Game {id}: {}", fits.join("; ")),
+        }
+    }
+}
+
+pub fn print_json<T: Serialize>(records: &[T]) {
+    println!(
+        "{}",
+        serde_json::to_string_pretty(records).expect("records always serialize")
+    );
+}
@@ -0,0 +1,73 @@
+use crate::error::AocError;
+use std::any::Any;
+use std::fmt::Display;
+
+/// A single day's puzzle: parse the input once, then answer both parts from it.
+pub trait Solution {
+    const DAY: u8;
+
+    type Parsed: 'static;
+    type Answer1: Display;
+    type Answer2: Display;
+
+    fn parse(input: &str) -> Result<Self::Parsed, AocError>;
+    fn part1(parsed: &Self::Parsed) -> Result<Self::Answer1, AocError>;
+    fn part2(parsed: &Self::Parsed) -> Result<Self::Answer2, AocError>;
+}
+
+/// Type-erased view of a `Solution`, so days with different answer types can sit in one registry.
+/// The methods are named apart from `Solution`'s so importing both traits stays unambiguous.
+pub trait Runner: Sync {
+    fn day(&self) -> u8;
+    fn parse_any(&self, input: &str) -> Result<Box<dyn Any>, AocError>;
+    fn run_part1(&self, parsed: &dyn Any) -> Result<String, AocError>;
+    fn run_part2(&self, parsed: &dyn Any) -> Result<String, AocError>;
+}
+
+impl<S: Solution + Sync> Runner for S {
+    fn day(&self) -> u8 {
+        S::DAY
+    }
+
+    fn parse_any(&self, input: &str) -> Result<Box<dyn Any>, AocError> {
+        Ok(Box::new(S::parse(input)?))
+    }
+
+    fn run_part1(&self, parsed: &dyn Any) -> Result<String, AocError> {
+        S::part1(downcast::<S>(parsed)?).map(|a| a.to_string())
+    }
+
+    fn run_part2(&self, parsed: &dyn Any) -> Result<String, AocError> {
+        S::part2(downcast::<S>(parsed)?).map(|a| a.to_string())
+    }
+}
+
+fn downcast<S: Solution>(parsed: &dyn Any) -> Result<&S::Parsed, AocError> {
+    parsed
+        .downcast_ref::<S::Parsed>()
+        .ok_or(AocError::WrongDay { day: S::DAY })
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::day01::Day01;
+    use crate::day03::Day03;
+    use crate::*;
+
+    #[test]
+    fn test_both_traits_in_scope() {
+        let parsed = Day03::parse("467..\n...*.").unwrap();
+        assert_eq!(Day03::part1(&parsed).unwrap(), 467);
+
+        let runner: &dyn Runner = &Day03;
+        let parsed = runner.parse_any("467..\n...*.").unwrap();
+        assert_eq!(runner.run_part1(&*parsed).unwrap(), "467");
+    }
+
+    #[test]
+    fn test_wrong_day() {
+        let parsed = (&Day03 as &dyn Runner).parse_any("1.").unwrap();
+        let err = (&Day01 as &dyn Runner).run_part1(&*parsed).unwrap_err();
+        assert!(matches!(err, AocError::WrongDay { day: 1 }));
+    }
+}